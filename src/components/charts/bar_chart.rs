@@ -0,0 +1,41 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::tui::{group_by, models};
+
+// bars only take integers, values are scaled to this resolution
+const SCALE: f64 = 1000.0;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let Some((name, series)) = app.group_by.chart_series() else { return };
+    let max = series.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let bars: Vec<Bar> = series
+        .iter()
+        .map(|(label, value)| {
+            let scaled = match max > 0.0 {
+                true => (value.max(0.0) / max * SCALE) as u64,
+                false => 0,
+            };
+            Bar::default()
+                .label(Line::from(label.as_str()))
+                .value(scaled)
+                .text_value(group_by::format_value(*value))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .title(
+                    block::Title::default()
+                        .content(name.cyan().bold())
+                        .alignment(Alignment::Center),
+                )
+                .borders(Borders::ALL),
+        )
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(9)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(app.colors.selected_style_fg))
+        .value_style(Style::default().fg(app.colors.buffer_bg).bg(app.colors.selected_style_fg));
+    f.render_widget(chart, area);
+}
@@ -0,0 +1,55 @@
+use ratatui::{prelude::*, widgets::{canvas::{self, Canvas, Points, Rectangle}, *}};
+use crate::tui::{group_by::format_value, models};
+
+// box width in x units, every box gets one unit
+const BOX_WIDTH: f64 = 0.5;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    app.update_box_plot();
+    let box_plot = &app.box_plot;
+    let block = Block::new().borders(Borders::all()).title(
+        block::Title::default()
+            .content(format!("{} | (B) split", box_plot.title).cyan().bold())
+            .alignment(Alignment::Center),
+    );
+    let Some((min, max)) = box_plot.bounds() else {
+        f.render_widget(Paragraph::new("no numeric values to plot").block(block), area);
+        return;
+    };
+    // room below the boxes for their labels and left of them for the scale
+    let pad = ((max - min) * 0.1).max(1.0);
+    let (y0, y1) = (min - pad, max + pad / 2.0);
+    let n = box_plot.boxes.len() as f64;
+    let box_color = app.colors.selected_style_fg;
+    let outlier_color = app.colors.outlier;
+
+    let canvas = Canvas::default()
+        .block(block)
+        .marker(Marker::Braille)
+        .x_bounds([-1.0, n])
+        .y_bounds([y0, y1])
+        .paint(|ctx| {
+            for (i, b) in box_plot.boxes.iter().enumerate() {
+                let x = i as f64 + 0.5;
+                let (left, right) = (x - BOX_WIDTH / 2.0, x + BOX_WIDTH / 2.0);
+                let line = |x1: f64, y1: f64, x2: f64, y2: f64| canvas::Line { x1, y1, x2, y2, color: box_color };
+                ctx.draw(&Rectangle { x: left, y: b.q1, width: BOX_WIDTH, height: b.q3 - b.q1, color: box_color });
+                ctx.draw(&canvas::Line { x1: left, y1: b.median, x2: right, y2: b.median, color: Color::White });
+                // whiskers and their caps
+                ctx.draw(&line(x, b.q3, x, b.upper_whisker));
+                ctx.draw(&line(x, b.q1, x, b.lower_whisker));
+                ctx.draw(&line(x - BOX_WIDTH / 4.0, b.upper_whisker, x + BOX_WIDTH / 4.0, b.upper_whisker));
+                ctx.draw(&line(x - BOX_WIDTH / 4.0, b.lower_whisker, x + BOX_WIDTH / 4.0, b.lower_whisker));
+                let points: Vec<(f64, f64)> = b.outliers.iter().map(|v| (x, *v)).collect();
+                ctx.draw(&Points { coords: &points, color: outlier_color });
+            }
+            ctx.layer();
+            for (i, b) in box_plot.boxes.iter().enumerate() {
+                ctx.print(i as f64 + 0.5 - BOX_WIDTH / 2.0, y0, b.label.clone().gray());
+            }
+            ctx.print(-1.0, max, format_value(max).gray());
+            ctx.print(-1.0, min, format_value(min).gray());
+        });
+    f.render_widget(canvas, area);
+}
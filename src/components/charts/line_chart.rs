@@ -1,72 +1,56 @@
 use ratatui::{prelude::*, widgets::*};
-use crate::tui::models;
+use crate::tui::{models, plot::{self, Plot}};
 
 
 pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
-    let mut a: Vec<Dataset<'_>> = [].to_vec();
-    let styles = [
-        Color::Black,
-        Color::Red,
-        Color::Green,
-        Color::Yellow,
-        Color::Blue,
-        Color::Magenta,
-        Color::Cyan,
-        Color::Gray,
-        Color::DarkGray,
-        Color::LightRed,
-        Color::LightGreen,
-        Color::LightYellow,
-        Color::LightBlue,
-        Color::LightMagenta,
-    ];
-    let mut iter = 0;
-    for data in &app.plot_data {
-        let h = &app.grouped_headers[iter];
-        a.push(
-            Dataset::default()
-                .name(format!("plot of {} against {}", h.0, h.1))
-                .marker(symbols::Marker::Braille)
-                .style(Style::default().fg(styles[iter]))
-                .graph_type(GraphType::Line)
-                .data(&data)
-            );
-            iter += 1;
-    }
+    let plot = plot::line_chart(app);
+    let title = format!("{} | (+/-) zoom (HJKL) pan (0) fit (a) axes", plot.title);
+    let chart = chart(&plot, title)
+        .legend_position(Some(LegendPosition::TopLeft))
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
+
+    f.render_widget(chart, area)
+}
 
-    let ((min_x, min_y), (max_x, max_y)) = app.plot_data.iter().flat_map(|v| v.iter()).fold(
-        ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY)),
-        |((min_x, min_y), (max_x, max_y)), &(x, y)| {
-            ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
-        },
-    );
+/// A ratatui chart of the plot's series, bounds and labels.
+pub fn chart(plot: &Plot, title: String) -> Chart<'_> {
+    let datasets = plot.series
+        .iter()
+        .map(|s| {
+            let dataset = Dataset::default()
+                .marker(s.marker)
+                .style(Style::default().fg(s.color))
+                .graph_type(s.graph_type)
+                .data(&s.points);
+            match &s.name {
+                Some(name) => dataset.name(name.as_str()),
+                None => dataset,
+            }
+        })
+        .collect();
 
-    let chart = Chart::new(a)
+    Chart::new(datasets)
         .block(
             Block::default()
                 .title(
                     block::Title::default()
-                        .content("Line chart".cyan().bold())
+                        .content(title.cyan().bold())
                         .alignment(Alignment::Center),
                 )
                 .borders(Borders::ALL),
         )
         .x_axis(
             Axis::default()
-                .title("X Axis")
+                .title(plot.x_title.as_str())
                 .style(Style::default().gray())
-                .bounds([min_x-10.0, max_x+10.0])
-                .labels(vec![format!("{min_x}").into(), format!("{max_x}").into()]),
+                .bounds(plot.x_bounds)
+                .labels(plot.x_labels.iter().map(|l| Span::from(l.as_str())).collect()),
         )
         .y_axis(
             Axis::default()
-                .title("Y Axis")
+                .title(plot.y_title.as_str())
                 .style(Style::default().gray())
-                .bounds([min_y-10.0, max_y+10.0])
-                .labels(vec![format!("{min_y}").into(), format!("{max_y}").into()]),
+                .bounds(plot.y_bounds)
+                .labels(plot.y_labels.iter().map(|l| Span::from(l.as_str())).collect()),
         )
-        .legend_position(Some(LegendPosition::TopLeft))
-        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
-
-    f.render_widget(chart, area)
-}
\ No newline at end of file
+}
@@ -1,2 +1,5 @@
 pub mod line_chart;
-pub mod scatter_plot;
\ No newline at end of file
+pub mod scatter_plot;
+pub mod bar_chart;
+pub mod box_plot;
+pub mod time_series;
@@ -1,62 +1,51 @@
 use ratatui::{prelude::*, widgets::*};
-use crate::tui::models;
-
+use crate::components::charts::line_chart;
+use crate::tui::{models, plot};
 
 
 pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
-    let styles = [
-        Style::new().yellow(),
-        Style::new().green(),
-        Style::new().blue(),
-        Style::new().gray(),
-        Style::new().cyan(),
-        Style::new().yellow(),
-        Style::new().green(),
-        Style::new().yellow(),
-        Style::new().green(),
-    ];
+    let plot = plot::scatter_plot(app);
+    let chart = line_chart::chart(&plot, plot.title.clone())
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
 
-    let mut a: Vec<Dataset<'_>> = [].to_vec();
-    let mut iter = 0;
-    for data in &app.plot_data {
-        let h = &app.grouped_headers[iter];
-        a.push(
-            Dataset::default()
-            .name(format!("plot of {} against {}", h.0, h.1))
-                .marker(Marker::Dot)
-                .graph_type(GraphType::Scatter)
-                .style(styles[iter])
-                .data(&data)
-        );
-        iter += 1;
-    }
+    f.render_widget(chart, area);
+}
 
-    let ((min_x, min_y), (max_x, max_y)) = app.plot_data.iter().flat_map(|v| v.iter()).fold(
+/// Scatter plot of the column pair selected in the correlation matrix.
+pub fn render_pair(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let Some((x_label, y_label, points)) = app.correlation.selected_pair() else { return };
+    let ((min_x, min_y), (max_x, max_y)) = points.iter().fold(
         ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY)),
         |((min_x, min_y), (max_x, max_y)), &(x, y)| {
             ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
         },
     );
+    let dataset = Dataset::default()
+        .name(format!("{} against {}", y_label, x_label))
+        .marker(Marker::Dot)
+        .graph_type(GraphType::Scatter)
+        .style(Style::new().fg(app.colors.selected_style_fg))
+        .data(&points);
 
-    let chart = Chart::new(a)
+    let chart = Chart::new(vec![dataset])
         .block(
             Block::new().borders(Borders::all()).title(
                 block::Title::default()
-                    .content("Scatter chart".cyan().bold())
+                    .content(format!("{} vs {}", y_label, x_label).cyan().bold())
                     .alignment(Alignment::Center),
             ),
         )
         .x_axis(
             Axis::default()
-                .title("X Axis")
-                .bounds([min_x-10.0, max_x+10.0])
+                .title(x_label)
+                .bounds([min_x, max_x])
                 .style(Style::default().fg(Color::Gray))
                 .labels(vec![format!("{min_x}").into(), format!("{max_x}").into()]),
         )
         .y_axis(
             Axis::default()
-                .title("Y Axis")
-                .bounds([min_y-10.0, max_y+10.0])
+                .title(y_label)
+                .bounds([min_y, max_y])
                 .style(Style::default().fg(Color::Gray))
                 .labels(vec![format!("{min_y}").into(), format!("{max_y}").into()]),
         )
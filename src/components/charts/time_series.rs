@@ -0,0 +1,15 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::components::charts::line_chart;
+use crate::tui::{models, plot};
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let plot = plot::time_series(app);
+    let title = format!("{} | (T) change", plot.title);
+    if plot.series.iter().all(|s| s.points.is_empty()) {
+        let block = Block::default().title(title).borders(Borders::ALL);
+        f.render_widget(Paragraph::new("no dated values to plot").block(block), area);
+        return;
+    }
+    f.render_widget(line_chart::chart(&plot, title), area)
+}
@@ -1 +1,2 @@
-pub mod items;
\ No newline at end of file
+pub mod items;
+pub mod sheets;
@@ -0,0 +1,30 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::tui::models;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let Some(mut picker) = app.sheet_picker.clone() else { return };
+    let height = (app.sheets.len() as u16 + 2).min(area.height);
+    let width = (area.width / 3).max(30).min(area.width);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = app.sheets.iter().map(|s| ListItem::new(s.as_str())).collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title("Choose a sheet (↲ to open)")
+                .borders(Borders::ALL)
+                .border_style(Style::new().fg(app.colors.footer_border_color))
+                .border_type(BorderType::Double),
+        )
+        .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED).fg(app.colors.selected_style_fg))
+        .highlight_symbol(" ⮞ ");
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut picker);
+}
@@ -0,0 +1,60 @@
+use ratatui::{prelude::*, widgets::*};
+use style::palette::tailwind;
+use crate::tui::{correlation::Method, models};
+
+const CELL_WIDTH: u16 = 9;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    app.update_correlation();
+    let header_style = Style::default()
+        .fg(app.colors.header_fg)
+        .bg(app.colors.header_bg);
+    let correlation = &app.correlation;
+    // positive correlations in the palette, negative ones in orange
+    let positive = app.colors.heatmap;
+    let negative = [tailwind::ORANGE.c100, tailwind::ORANGE.c300, tailwind::ORANGE.c500, tailwind::ORANGE.c700, tailwind::ORANGE.c900];
+    let shade = |r: f64| {
+        let step = (r.abs() * (positive.len() - 1) as f64).round() as usize;
+        let bg = if r < 0.0 { negative[step] } else { positive[step] };
+        let fg = if step < positive.len() / 2 { Color::Black } else { Color::White };
+        Style::new().bg(bg).fg(fg)
+    };
+
+    let method = match correlation.method {
+        Method::Pearson => "Pearson",
+        Method::Spearman => "Spearman",
+    };
+    let title = format!("{} correlation | (c) method | (H/L/J/K) move", method);
+
+    let header = std::iter::once(Cell::from(""))
+        .chain(correlation.labels.iter().map(|l| Cell::from(l.as_str())))
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+    let rows = correlation.matrix.iter().enumerate().map(|(i, values)| {
+        std::iter::once(Cell::from(correlation.labels[i].as_str()).style(header_style))
+            .chain(values.iter().enumerate().map(|(j, r)| {
+                let cell = match r {
+                    Some(r) => Cell::from(format!("{:.3}", r)).style(shade(*r)),
+                    None => Cell::from("–"),
+                };
+                match (i, j) == correlation.cursor {
+                    true => cell.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+                    false => cell,
+                }
+            }))
+            .collect::<Row>()
+            .style(Style::new().fg(app.colors.row_fg).bg(app.colors.normal_row_color))
+            .height(1)
+    });
+    let width: Vec<Constraint> = std::iter::once(Constraint::Length(CELL_WIDTH + 4))
+        .chain(correlation.labels.iter().map(|_| Constraint::Length(CELL_WIDTH)))
+        .collect();
+
+    let t = Table::new(rows, width)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .bg(app.colors.buffer_bg);
+    f.render_widget(t, area);
+}
@@ -0,0 +1,37 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::tui::models;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let header_style = Style::default()
+        .fg(app.colors.header_fg)
+        .bg(app.colors.header_bg);
+
+    let header = ["Property", "Value"]
+        .iter()
+        .cloned()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(header_style)
+        .height(2);
+
+    let mut info = app.file_info.clone();
+    if let Some(source) = &app.source {
+        info.push(("Chunks loaded".to_string(), format!("{} ({} rows in memory)", source.loaded, app.items.len())));
+    }
+
+    let rows = info.iter().enumerate().map(|(i, (key, value))| {
+        let color = match i % 2 {
+            0 => app.colors.normal_row_color,
+            _ => app.colors.alt_row_color,
+        };
+        Row::new(vec![Cell::from(key.as_str()), Cell::from(value.as_str())])
+            .style(Style::new().fg(app.colors.row_fg).bg(color))
+            .height(1)
+    });
+
+    let t = Table::new(rows, [Constraint::Percentage(30), Constraint::Percentage(70)])
+        .header(header)
+        .bg(app.colors.buffer_bg);
+    f.render_widget(t, area);
+}
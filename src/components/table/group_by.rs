@@ -0,0 +1,72 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::tui::{group_by::Aggregate, models};
+use strum::IntoEnumIterator;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    app.update_group_by();
+    let header_style = Style::default()
+        .fg(app.colors.header_fg)
+        .bg(app.colors.header_bg);
+    let selected_style = Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .fg(app.colors.selected_style_fg);
+    let partial = app.partial();
+    let group_by = &mut app.group_by;
+
+    let aggregates: Vec<String> = Aggregate::iter()
+        .enumerate()
+        .map(|(i, a)| match group_by.aggregates.contains(&a) {
+            true => format!("[{}] {}", i + 1, a),
+            false => format!(" {}  {}", i + 1, a),
+        })
+        .collect();
+    let mut title = match group_by.keys.is_empty() {
+        true => "Group By: no keys yet, press (g) on a column in the Data Explorer".to_string(),
+        false => format!("Group By | (1-7) aggregates: {} | (o) sort", aggregates.join(" ")),
+    };
+    if let Some(partial) = partial {
+        title = format!("{} | {}", title, partial);
+    }
+
+    let header = group_by.headers
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            let marker = match group_by.sort {
+                Some((c, true)) if c == i => " ▼",
+                Some((c, false)) if c == i => " ▲",
+                _ => "",
+            };
+            let cell = Cell::from(format!("{}{}", h, marker));
+            match i == group_by.column {
+                true => cell.style(Style::default().add_modifier(Modifier::REVERSED)),
+                false => cell,
+            }
+        })
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+    let rows = group_by.rows.iter().enumerate().map(|(i, data)| {
+        let color = match i % 2 {
+            0 => app.colors.normal_row_color,
+            _ => app.colors.alt_row_color,
+        };
+        data.iter()
+            .map(|c| Cell::from(c.as_str()))
+            .collect::<Row>()
+            .style(Style::new().fg(app.colors.row_fg).bg(color))
+            .height(1)
+    });
+    let space = 100 / group_by.headers.len().max(1);
+    let width: Vec<Constraint> = group_by.headers.iter().map(|_| Constraint::Percentage(space as u16)).collect();
+
+    let t = Table::new(rows, width)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .highlight_style(selected_style)
+        .highlight_symbol(" ⮞ ")
+        .bg(app.colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always);
+    f.render_stateful_widget(t, area, &mut group_by.state);
+}
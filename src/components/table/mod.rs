@@ -1,2 +1,9 @@
+#[allow(clippy::module_inception)]
 pub mod table;
-pub mod statistics;
\ No newline at end of file
+pub mod statistics;
+pub mod file_info;
+pub mod sql_console;
+pub mod group_by;
+pub mod pivot;
+pub mod correlation;
+pub mod quality;
@@ -0,0 +1,85 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::tui::models;
+
+const CELL_WIDTH: u16 = 14;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    app.update_pivot();
+    let header_style = Style::default()
+        .fg(app.colors.header_fg)
+        .bg(app.colors.header_bg);
+    let selected_style = Style::default()
+        .add_modifier(Modifier::BOLD)
+        .fg(app.colors.selected_style_fg);
+    let partial = app.partial();
+    let pivot = &mut app.pivot;
+
+    let mut title = match pivot.spec {
+        Some(_) => format!("Pivot: {} | (H/L) scroll | (p) change | (e) export", pivot.title),
+        None => "Pivot: press (p) and enter rows, columns[, aggregate, value], e.g. state, pick, mean, amout".to_string(),
+    };
+    if let Some(partial) = partial {
+        title = format!("{} | {}", title, partial);
+    }
+    let bounds = pivot.bounds();
+    let heatmap = app.colors.heatmap;
+    let shade = |v: f64| {
+        let (min, max) = bounds.unwrap_or((v, v));
+        let step = match max > min {
+            true => ((v - min) / (max - min) * (heatmap.len() - 1) as f64).round() as usize,
+            false => heatmap.len() / 2,
+        };
+        // dark text on the light end of the scale
+        let fg = if step < heatmap.len() / 2 { Color::Black } else { Color::White };
+        Style::new().bg(heatmap[step]).fg(fg)
+    };
+
+    // the row key and the totals stay put, the value columns in between scroll with H/L
+    let fits = ((area.width.saturating_sub(6) / CELL_WIDTH) as usize).saturating_sub(2).max(1);
+    let last = pivot.headers.len().saturating_sub(1);
+    let visible: Vec<usize> = match pivot.headers.is_empty() {
+        // nothing pivoted yet
+        true => vec![],
+        false => std::iter::once(0)
+            .chain((1 + pivot.offset..last).take(fits))
+            .chain(std::iter::once(last))
+            .collect(),
+    };
+
+    let header = visible
+        .iter()
+        .map(|j| Cell::from(pivot.headers[*j].as_str()))
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+    let rows = pivot.rows.iter().enumerate().map(|(i, data)| {
+        let totals = Style::new().fg(app.colors.header_fg).bg(app.colors.header_bg).bold();
+        visible
+            .iter()
+            .map(|j| (*j, &data[*j]))
+            .map(|(j, c)| {
+                let cell = Cell::from(c.as_str());
+                let value = pivot.cells.get(i).and_then(|r| j.checked_sub(1).and_then(|j| r.get(j))).copied().flatten();
+                match (j, value) {
+                    (0, _) => cell.style(totals),
+                    (_, Some(v)) => cell.style(shade(v)),
+                    _ if i == pivot.cells.len() || j == data.len() - 1 => cell.style(totals),
+                    _ => cell,
+                }
+            })
+            .collect::<Row>()
+            .style(Style::new().fg(app.colors.row_fg).bg(app.colors.normal_row_color))
+            .height(1)
+    });
+    let width: Vec<Constraint> = visible.iter().map(|_| Constraint::Length(CELL_WIDTH)).collect();
+
+    let t = Table::new(rows, width)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .highlight_style(selected_style)
+        .highlight_symbol(" ⮞ ")
+        .bg(app.colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always);
+    f.render_stateful_widget(t, area, &mut pivot.state);
+}
@@ -0,0 +1,66 @@
+use ratatui::{prelude::*, widgets::*};
+use style::palette::tailwind;
+use crate::tui::{models, quality};
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    app.update_quality();
+    let header_style = Style::default()
+        .fg(app.colors.header_fg)
+        .bg(app.colors.header_bg);
+    let warning_style = Style::new().fg(tailwind::RED.c400).add_modifier(Modifier::BOLD);
+
+    let header = quality::HEADERS
+        .iter()
+        .map(|h| Cell::from(*h))
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+    let rows = app.quality.iter().enumerate().map(|(i, column)| {
+        let color = match i % 2 {
+            0 => app.colors.normal_row_color,
+            _ => app.colors.alt_row_color,
+        };
+        let record = column.to_record();
+        let warnings = record.len() - 1;
+        record
+            .into_iter()
+            .enumerate()
+            .map(|(j, value)| match j {
+                // flag the figures a warning is about, not just the text
+                1 | 3 if column.unparseable > 0 => Cell::from(value).style(warning_style),
+                2 if column.empty > 0 => Cell::from(value).style(Style::new().fg(tailwind::AMBER.c400)),
+                8 if column.whitespace > 0 => Cell::from(value).style(Style::new().fg(tailwind::AMBER.c400)),
+                j if j == warnings => Cell::from(value).style(warning_style),
+                _ => Cell::from(value),
+            })
+            .collect::<Row>()
+            .style(Style::new().fg(app.colors.row_fg).bg(color))
+            .height(1)
+    });
+    let longest_name = app.table_header.iter().map(|h| h.chars().count()).max().unwrap_or(0);
+    let mut width: Vec<u16> = quality::HEADERS
+        .iter()
+        .map(|h| match *h {
+            "column" => longest_name.clamp(10, 24) as u16,
+            h => h.len().max(7) as u16,
+        })
+        .collect();
+    // the warnings get whatever is left of the row
+    let used: u16 = width.iter().map(|w| w + 1).sum();
+    if let Some(last) = width.last_mut() {
+        *last = area.width.saturating_sub(used - *last + 2).max(*last);
+    }
+    let width: Vec<Constraint> = width.into_iter().map(Constraint::Length).collect();
+
+    let flagged = app.quality.iter().filter(|c| !c.warnings.is_empty()).count();
+    let mut title = format!("Data Quality | {} of {} columns with warnings", flagged, app.quality.len());
+    if let Some(partial) = app.partial() {
+        title = format!("{} | {}", title, partial);
+    }
+    let t = Table::new(rows, width)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .bg(app.colors.buffer_bg);
+    f.render_widget(t, area);
+}
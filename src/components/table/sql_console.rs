@@ -0,0 +1,69 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::tui::models;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let [info_area, results_area] = Layout::vertical([Constraint::Length(4), Constraint::Min(0)]).areas(area);
+    let console = &mut app.sql_console;
+
+    let tables = match console.tables.is_empty() {
+        true => "(the loaded file becomes a table on the first query)".to_string(),
+        false => console.tables.join(", "),
+    };
+    let last = match &console.error {
+        Some(e) => Line::from(Span::styled(format!("error: {}", e), Style::new().fg(Color::Red))),
+        None => Line::from(format!("query: {}", console.query)),
+    };
+    let info = Paragraph::new(vec![Line::from(format!("tables: {}", tables)), last])
+        .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
+        .block(
+            Block::default()
+                .title("SQL console (s) query | .load <path> [name] | .export <path> | .tables")
+                .borders(Borders::ALL)
+                .border_style(Style::new().fg(app.colors.footer_border_color)),
+        );
+    f.render_widget(info, info_area);
+
+    if console.headers.is_empty() {
+        return;
+    }
+    let header_style = Style::default()
+        .fg(app.colors.header_fg)
+        .bg(app.colors.header_bg);
+    let selected_style = Style::default()
+        .add_modifier(Modifier::REVERSED)
+        .fg(app.colors.selected_style_fg);
+
+    let header = console.headers
+        .iter()
+        .cloned()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+    let rows = console.records.iter().enumerate().map(|(i, data)| {
+        let color = match i % 2 {
+            0 => app.colors.normal_row_color,
+            _ => app.colors.alt_row_color,
+        };
+        data.iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(app.colors.row_fg).bg(color))
+            .height(1)
+    });
+    let space = 100 / console.headers.len();
+    let width: Vec<Constraint> = console.headers.iter().map(|_| Constraint::Percentage(space as u16)).collect();
+
+    let t = Table::new(rows, width)
+        .header(header)
+        .highlight_style(selected_style)
+        .highlight_symbol(" ⮞ ")
+        .bg(app.colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always);
+    f.render_stateful_widget(t, results_area, &mut console.state);
+
+    let mut scroll_state = ScrollbarState::new(console.records.len().saturating_sub(1))
+        .position(console.state.selected().unwrap_or(0));
+    crate::components::utils::scroll_bar::render(f, &mut scroll_state, results_area);
+}
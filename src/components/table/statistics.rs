@@ -28,6 +28,13 @@ pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
 
 
     for el in &app.raw_data {
+        // e.g. a numeric column of a parquet file that is all null so far
+        if el.data.is_empty() {
+            for col in cols.iter_mut() {
+                col.push("-".to_string());
+            }
+            continue;
+        }
         let statistics = stats::Data{
             data: el.data.clone(),
         };
@@ -64,13 +71,13 @@ pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
 
     let bar = " ⮞ ";
     let mut width: Vec<Constraint> = [].to_vec();
-    let space = 100/app.table_header.len();
-    for (_, _) in app.table_header.iter().enumerate() {
+    let space = 100/app.table_header.len().max(1);
+    for _ in app.table_header.iter() {
         width.push(Constraint::Percentage(space as u16))
     }
     
     
-    let t = Table::new(rows, width)
+    let mut t = Table::new(rows, width)
     .header(header)
     .highlight_style(selected_style)
     .highlight_symbol(Text::from(vec![
@@ -81,5 +88,8 @@ pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
     ]))
     .bg(app.colors.buffer_bg)
     .highlight_spacing(HighlightSpacing::Always);
+    if let Some(partial) = app.partial() {
+        t = t.block(Block::new().title(format!("Statistics | {}", partial)));
+    }
     f.render_stateful_widget(t, area, &mut app.app_state);
 }
@@ -1,7 +1,8 @@
 use ratatui::{prelude::*, widgets::*};
-use crate::tui::models;
+use crate::tui::{diff::Change, models};
 
 pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    app.update_view();
     let header_style = Style::default()
         .fg(app.colors.header_fg)
         .bg(app.colors.header_bg);
@@ -9,38 +10,78 @@ pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
         .add_modifier(Modifier::REVERSED)
         .fg(app.colors.selected_style_fg);
 
-    let header = app.table_header
-        .iter()
-        .cloned()
-        .map(Cell::from)
+    // a filtered view labels its rows in an extra first column
+    let label = app.view.as_ref().filter(|v| !v.labels.is_empty());
+    let header = label
+        .map(|v| Cell::from(v.label.as_str()))
+        .into_iter()
+        .chain(app.table_header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| match app.group_by.keys.contains(&i) {
+                true => Cell::from(format!("▣ {}", h)),
+                false => Cell::from(h.as_str()),
+            }))
         .collect::<Row>()
         .style(header_style)
         .height(2);
 
-    let rows = app.items.iter().enumerate().map(|(i, data)| {
+    let cursor_style = Style::default()
+        .add_modifier(Modifier::REVERSED | Modifier::BOLD);
+    let outlier_style = Style::default()
+        .fg(app.colors.outlier)
+        .add_modifier(Modifier::BOLD);
+
+    let changed_style = Style::default()
+        .fg(Color::Black)
+        .bg(app.colors.changed);
+
+    let rows = (0..app.row_count()).filter_map(|i| Some((i, app.record(i)?))).map(|(i, data)| {
         let color = match i % 2 {
             0 => app.colors.normal_row_color,
             _ => app.colors.alt_row_color,
         };
+        // rows of a diff are coloured by what happened to them
+        let change = app.diff.as_ref().and_then(|d| d.changes.get(app.item_index(i)?));
+        let fg = match change {
+            Some(Change::Added) => app.colors.added,
+            Some(Change::Removed) => app.colors.removed,
+            _ => app.colors.row_fg,
+        };
 
         let item: Vec<&str> = data.iter().collect();
-        // println!("{:?}", item);
-        item.iter()
-            .cloned()
-            .map(|content| Cell::from(Text::from(format!("\n{}\n", content))))
+        label
+            .map(|v| Cell::from(Text::from(format!("\n{}\n", v.labels[i]))).style(header_style))
+            .into_iter()
+            .chain(item.iter()
+                .cloned()
+                .enumerate()
+                .map(|(j, content)| {
+                    let cell = Cell::from(Text::from(format!("\n{}\n", content)));
+                    let changed = matches!(change, Some(Change::Changed(cells)) if cells.contains(&j));
+                    match (app.is_selected(i, j), changed, app.outliers.is_outlier_cell(j, content)) {
+                        (true, _, _) => cell.style(cursor_style),
+                        (false, true, _) => cell.style(changed_style),
+                        (false, false, true) => cell.style(outlier_style),
+                        _ => cell,
+                    }
+                }))
             .collect::<Row>()
-            .style(Style::new().fg(app.colors.row_fg).bg(color))
+            .style(Style::new().fg(fg).bg(color))
             .height(2)
     });
     let bar = " ⮞ ";
-    let mut width: Vec<Constraint> = [].to_vec();
-    let space = 100/app.table_header.len();
-    for (_, _) in app.table_header.iter().enumerate() {
+    let mut width: Vec<Constraint> = label
+        .map(|v| Constraint::Length(v.labels.iter().map(|l| l.chars().count()).max().unwrap_or(0).max(v.label.len()) as u16))
+        .into_iter()
+        .collect();
+    let space = 100/app.table_header.len().max(1);
+    for _ in app.table_header.iter() {
         width.push(Constraint::Percentage(space as u16))
     }
 
 
-    let t = Table::new(rows, width)
+    let mut t = Table::new(rows, width)
     .header(header)
     .highlight_style(selected_style)
     .highlight_symbol(Text::from(vec![
@@ -51,5 +92,10 @@ pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
     ]))
     .bg(app.colors.buffer_bg)
     .highlight_spacing(HighlightSpacing::Always);
+    if let Some(view) = &app.view {
+        t = t.block(Block::new().title(view.title.as_str()));
+    } else if let Some(diff) = &app.diff {
+        t = t.block(Block::new().title(format!("Diff: {}", diff.summary())));
+    }
     f.render_stateful_widget(t, area, &mut app.app_state);
 }
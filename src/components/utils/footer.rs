@@ -3,11 +3,15 @@ use crate::tui::models;
 
 
 const INFO_TEXT: &str =
-    "(Esc) quit | (↑) move up | (↓) move down | (→) next color | (←) previous color | ↲ for Menu";
+    "(Esc) quit | (↑) move up | (↓) move down | (H/L) column | (→) next color | (←) previous color | ↲ for Menu | (y/Y) yank cell/row | (v) select";
 
 
 pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
-    let info_footer = Paragraph::new(Line::from(INFO_TEXT))
+    let text = match app.status.is_empty() {
+        true => INFO_TEXT.to_string(),
+        false => app.status.clone(),
+    };
+    let info_footer = Paragraph::new(Line::from(text))
         .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
         .centered()
         .block(
@@ -1,2 +1,3 @@
 pub mod scroll_bar;
-pub mod footer;
\ No newline at end of file
+pub mod footer;
+pub mod prompt;
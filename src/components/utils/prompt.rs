@@ -0,0 +1,23 @@
+use ratatui::{prelude::*, widgets::{block::Title, *}};
+use crate::tui::models;
+
+
+pub fn render(f: &mut Frame, app: &mut models::App, area: Rect) {
+    let Some(prompt) = &app.prompt else { return };
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled(format!(" {} › ", prompt.kind.label()), Style::new().fg(app.colors.selected_style_fg).bold()),
+        Span::raw(prompt.input.as_str()),
+        Span::styled("█", Style::new().fg(app.colors.selected_style_fg)),
+    ]))
+    .style(Style::new().fg(app.colors.row_fg).bg(app.colors.buffer_bg))
+    .block(
+        Block::default()
+            .title("↲ run | Esc cancel")
+            .title(Title::from(prompt.hint.as_str()).alignment(Alignment::Right))
+            .borders(Borders::ALL)
+            .border_style(Style::new().fg(app.colors.footer_border_color))
+            .border_type(BorderType::Double),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(input, area);
+}
@@ -4,8 +4,28 @@ use std::error::Error;
 mod tui;
 mod components;
 
+const USAGE: &str = "usage: csv-grep [-f|--follow] <file>...
+       csv-grep profile <file>
+       csv-grep diff <old> <new> [--key column[,column]] [--print]
+       csv-grep concat <files> [--intersect] [--source[=column]] [-o output.csv]
+       csv-grep run <pipeline.toml|json> <input> [-o output.csv]";
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let file_path = std::env::args().nth(1).expect("no file path provided");
-    tui::main::main(file_path)
-}
\ No newline at end of file
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [command, file_path] if command == "profile" => tui::quality::print(file_path.clone()),
+        [command, rest @ ..] if command == "diff" => tui::diff::main(rest),
+        [command, rest @ ..] if command == "concat" => tui::concat::main(rest),
+        [command, rest @ ..] if command == "run" => tui::pipeline::main(rest),
+        _ => {
+            let follow = args.iter().any(|a| a == "--follow" || a == "-f");
+            let args: Vec<String> = args.into_iter().filter(|a| a != "--follow" && a != "-f").collect();
+            let file_paths = tui::session::expand(&args);
+            if file_paths.is_empty() {
+                eprintln!("{}", USAGE);
+                std::process::exit(2);
+            }
+            tui::main::main(file_paths, follow)
+        }
+    }
+}
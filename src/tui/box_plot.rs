@@ -0,0 +1,116 @@
+use csv::StringRecord;
+use std::collections::BTreeMap;
+use crate::tui::{models::Data, outliers, stats};
+
+// categories beyond this many are left out, keeping the largest groups
+const MAX_BOXES: usize = 20;
+
+
+/// The five numbers drawn for one box and the values beyond its whiskers.
+pub struct BoxStats {
+    pub label: String,
+    pub lower_whisker: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub upper_whisker: f64,
+    pub outliers: Vec<f64>,
+}
+
+impl BoxStats {
+    fn new(label: String, data: stats::Data) -> Option<BoxStats> {
+        if data.data.is_empty() {
+            return None;
+        }
+        let (q1, median, q3) = data.percentiles();
+        let (lo, hi) = outliers::tukey_fences(&data);
+        // whiskers reach the most extreme values still inside the fences
+        let inside = data.data.iter().copied().filter(|v| *v >= lo && *v <= hi);
+        Some(BoxStats {
+            label,
+            lower_whisker: inside.clone().fold(q1, f64::min),
+            q1,
+            median,
+            q3,
+            upper_whisker: inside.fold(q3, f64::max),
+            outliers: data.data.iter().copied().filter(|v| *v < lo || *v > hi).collect(),
+        })
+    }
+}
+
+/// Box plots of every numeric column, or of one column split by the values
+/// of a categorical one.
+pub struct BoxPlot {
+    // (value column, category column)
+    pub split: Option<(usize, usize)>,
+    pub title: String,
+    pub boxes: Vec<BoxStats>,
+    pub generation: Option<usize>,
+}
+
+impl BoxPlot {
+    pub fn new() -> BoxPlot {
+        BoxPlot { split: None, title: String::new(), boxes: vec![], generation: None }
+    }
+
+    /// Parses `value by category`, nothing meaning all numeric columns.
+    pub fn parse(input: &str, headers: &[String]) -> Result<Option<(usize, usize)>, String> {
+        if input.trim().is_empty() {
+            return Ok(None);
+        }
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name.trim())
+                .ok_or_else(|| format!("unknown column {}", name.trim()))
+        };
+        match input.split_once(" by ") {
+            Some((value, category)) => Ok(Some((column(value)?, column(category)?))),
+            None => Err("usage: value by category".to_string()),
+        }
+    }
+
+    pub fn compute(&mut self, headers: &[String], records: &[StringRecord], columns: &[Data]) {
+        match self.split {
+            None => {
+                self.title = "Box plot".to_string();
+                self.boxes = columns
+                    .iter()
+                    .filter_map(|c| BoxStats::new(c.label.clone(), stats::Data { data: c.data.clone() }))
+                    .collect();
+            }
+            Some((value, category)) => {
+                let mut groups: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+                for record in records {
+                    let Some(v) = record.get(value).and_then(|v| v.trim().parse::<f64>().ok()) else { continue };
+                    groups.entry(record.get(category).unwrap_or("").to_string()).or_default().push(v);
+                }
+                let total = groups.len();
+                let mut groups: Vec<(String, Vec<f64>)> = groups.into_iter().collect();
+                if total > MAX_BOXES {
+                    groups.sort_by_key(|(_, v)| std::cmp::Reverse(v.len()));
+                    groups.truncate(MAX_BOXES);
+                    groups.sort_by(|a, b| a.0.cmp(&b.0));
+                }
+                self.title = match total > MAX_BOXES {
+                    true => format!("Box plot of {} by {} ({} largest of {} groups)", headers[value], headers[category], MAX_BOXES, total),
+                    false => format!("Box plot of {} by {}", headers[value], headers[category]),
+                };
+                self.boxes = groups
+                    .into_iter()
+                    .filter_map(|(label, data)| BoxStats::new(label, stats::Data { data }))
+                    .collect();
+            }
+        }
+    }
+
+    /// Lowest and highest value drawn, outliers included.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        let values = self.boxes.iter().flat_map(|b| {
+            b.outliers.iter().copied().chain([b.lower_whisker, b.upper_whisker])
+        });
+        let min = values.clone().reduce(f64::min)?;
+        let max = values.reduce(f64::max)?;
+        Some((min, max))
+    }
+}
@@ -0,0 +1,125 @@
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use plotters::style::{register_font, FontStyle, FontTransform};
+use ratatui::{style::Color as TermColor, widgets::GraphType};
+use std::sync::OnceLock;
+use crate::tui::plot::Plot;
+
+pub const DEFAULT_SIZE: (u32, u32) = (1600, 800);
+// png text needs a real font, the first of these found is used
+const FONTS: [&str; 6] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+// the font file, read once; plotters borrows it for the rest of the program
+static FONT_DATA: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+// whether it was registered with plotters
+static FONT: OnceLock<bool> = OnceLock::new();
+
+
+/// Draws the plots side by side into an svg or png file, picked by the
+/// extension of `path`. Only png needs a font on this machine, svg leaves
+/// the text to whatever opens the file.
+pub fn export(path: &str, plots: &[Plot], size: (u32, u32)) -> Result<(), String> {
+    match path.rsplit('.').next().map(|e| e.to_lowercase()).as_deref() {
+        Some("svg") => draw(SVGBackend::new(path, size).into_drawing_area(), plots),
+        Some("png") if !*FONT.get_or_init(load_font) => Err("no font found to draw the labels of a png with, export to .svg instead".to_string()),
+        Some("png") => draw(BitMapBackend::new(path, size).into_drawing_area(), plots),
+        _ => Err("charts export to .svg or .png".to_string()),
+    }
+}
+
+fn load_font() -> bool {
+    let data = FONT_DATA.get_or_init(|| FONTS.iter().find_map(|path| std::fs::read(path).ok()));
+    data.as_deref().is_some_and(|bytes| register_font("sans-serif", FontStyle::Normal, bytes).is_ok())
+}
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, plots: &[Plot]) -> Result<(), String> {
+    let err = |e: DrawingAreaErrorKind<DB::ErrorType>| e.to_string();
+    root.fill(&WHITE).map_err(err)?;
+    for (area, plot) in root.split_evenly((1, plots.len().max(1))).iter().zip(plots) {
+        let (xs, ys) = (plot.x_bounds, plot.y_bounds);
+        let mut chart = ChartBuilder::on(area)
+            .margin(16)
+            .margin_top(56)
+            .margin_right(40)
+            .x_label_area_size(44)
+            .y_label_area_size(70)
+            .build_cartesian_2d(xs[0]..xs[1], ys[0]..ys[1])
+            .map_err(err)?;
+        chart.configure_mesh().disable_mesh().x_labels(0).y_labels(0).draw().map_err(err)?;
+
+        // all text is placed by hand at anchors rather than laid out by
+        // plotters, which would need the font metrics even for svg
+        let (left, top) = area.get_base_pixel();
+        let (width, _) = area.dim_in_pixel();
+        let (x_range, y_range) = chart.plotting_area().get_pixel_range();
+        let (center_x, center_y) = ((x_range.start + x_range.end) / 2, (y_range.start + y_range.end) / 2);
+        let title = ("sans-serif", 24).into_font().color(&BLACK).pos(Pos::new(HPos::Center, VPos::Top));
+        root.draw(&Text::new(plot.title.clone(), (left + width as i32 / 2, top + 16), title)).map_err(err)?;
+        let desc = ("sans-serif", 14).into_font().color(&BLACK);
+        let x_desc = desc.pos(Pos::new(HPos::Center, VPos::Top));
+        root.draw(&Text::new(plot.x_title.clone(), (center_x, y_range.end + 26), x_desc)).map_err(err)?;
+        let y_desc = desc.transform(FontTransform::Rotate270).pos(Pos::new(HPos::Center, VPos::Bottom));
+        root.draw(&Text::new(plot.y_title.clone(), (x_range.start - 60, center_y), y_desc)).map_err(err)?;
+
+        // the labels sit at evenly spaced points across the bounds, exactly
+        // where the terminal chart puts them, with a light grid behind
+        let ticks = |bounds: [f64; 2], n: usize| -> Vec<f64> {
+            (0..n).map(|i| bounds[0] + (bounds[1] - bounds[0]) * i as f64 / (n.max(2) - 1) as f64).collect()
+        };
+        let grid = RGBColor(229, 231, 235);
+        let font = ("sans-serif", 14).into_font();
+        for (x, label) in ticks(xs, plot.x_labels.len()).into_iter().zip(&plot.x_labels) {
+            chart.draw_series(LineSeries::new([(x, ys[0]), (x, ys[1])], grid)).map_err(err)?;
+            let (px, py) = chart.backend_coord(&(x, ys[0]));
+            let style = font.color(&BLACK).pos(Pos::new(HPos::Center, VPos::Top));
+            root.draw(&Text::new(label.clone(), (px, py + 6), style)).map_err(err)?;
+        }
+        for (y, label) in ticks(ys, plot.y_labels.len()).into_iter().zip(&plot.y_labels) {
+            chart.draw_series(LineSeries::new([(xs[0], y), (xs[1], y)], grid)).map_err(err)?;
+            let (px, py) = chart.backend_coord(&(xs[0], y));
+            let style = font.color(&BLACK).pos(Pos::new(HPos::Right, VPos::Center));
+            root.draw(&Text::new(label.clone(), (px - 6, py), style)).map_err(err)?;
+        }
+
+        let inside = |(x, y): &(f64, f64)| (xs[0]..=xs[1]).contains(x) && (ys[0]..=ys[1]).contains(y);
+        for series in &plot.series {
+            let color = rgb(series.color);
+            match series.graph_type {
+                GraphType::Line => {
+                    // lines leaving the zoomed window are cut, not drawn across
+                    for run in series.points.split(|p| !inside(p)).filter(|r| !r.is_empty()) {
+                        chart.draw_series(LineSeries::new(run.iter().copied(), color.stroke_width(2))).map_err(err)?;
+                    }
+                }
+                _ => {
+                    let points = series.points.iter().filter(|p| inside(p));
+                    chart.draw_series(points.map(|p| Circle::new(*p, 3, color.filled()))).map_err(err)?;
+                }
+            }
+        }
+    }
+    root.present().map_err(err)
+}
+
+/// Terminal colours in print, the light ones darkened to show on white.
+fn rgb(color: TermColor) -> RGBColor {
+    match color {
+        TermColor::Rgb(r, g, b) => RGBColor(r, g, b),
+        TermColor::Red | TermColor::LightRed => RGBColor(220, 38, 38),
+        TermColor::Green | TermColor::LightGreen => RGBColor(22, 163, 74),
+        TermColor::Yellow | TermColor::LightYellow => RGBColor(202, 138, 4),
+        TermColor::Blue | TermColor::LightBlue => RGBColor(37, 99, 235),
+        TermColor::Magenta | TermColor::LightMagenta => RGBColor(192, 38, 211),
+        TermColor::Cyan | TermColor::LightCyan => RGBColor(8, 145, 178),
+        TermColor::Gray | TermColor::DarkGray => RGBColor(107, 114, 128),
+        _ => RGBColor(0, 0, 0),
+    }
+}
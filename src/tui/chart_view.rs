@@ -0,0 +1,202 @@
+use crate::tui::group_by::format_value;
+
+// how much one zoom or pan step changes the visible window
+const ZOOM_STEP: f64 = 0.8;
+const PAN_STEP: f64 = 0.1;
+const MAX_TICKS: usize = 7;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// Zoom, pan, manual bounds and scale of one chart axis.
+#[derive(Clone, Debug)]
+pub struct AxisView {
+    // visible part of the fitted (or manual) range, as fractions of it
+    pub window: (f64, f64),
+    // bounds typed in by the user, in data units
+    pub manual: Option<(f64, f64)>,
+    pub log: bool,
+}
+
+impl AxisView {
+    fn new() -> AxisView {
+        AxisView { window: (0.0, 1.0), manual: None, log: false }
+    }
+
+    /// Maps a value onto the axis, `None` when it has no place on a log scale.
+    pub fn scale(&self, v: f64) -> Option<f64> {
+        match self.log {
+            true if v > 0.0 => Some(v.log10()),
+            true => None,
+            false => Some(v),
+        }
+    }
+
+    fn is_fitted(&self) -> bool {
+        self.window == (0.0, 1.0) && self.manual.is_none()
+    }
+
+    /// Bounds and tick labels for data spanning `fit` (already scaled).
+    pub fn resolve(&self, fit: (f64, f64)) -> ([f64; 2], Vec<String>) {
+        let (mut lo, mut hi) = match self.manual {
+            Some((a, b)) => (self.scale(a).unwrap_or(fit.0), self.scale(b).unwrap_or(fit.1)),
+            None => fit,
+        };
+        if !(hi - lo).is_finite() || hi <= lo {
+            (lo, hi) = (lo - 1.0, lo + 1.0);
+        }
+        if self.is_fitted() {
+            // widen to round numbers so the evenly spaced labels land on them
+            let mut step = nice_step((hi - lo) / (MAX_TICKS - 1) as f64);
+            if self.log && hi - lo >= 2.0 {
+                // whole decades, 10, 100, 1000 rather than 316.2278
+                step = step.ceil();
+            }
+            let (lo, hi) = ((lo / step).floor() * step, (hi / step).ceil() * step);
+            let ticks = ((hi - lo) / step).round() as usize + 1;
+            return ([lo, hi], (0..ticks).map(|i| self.label(lo + step * i as f64, step)).collect());
+        }
+        let span = hi - lo;
+        let (lo, hi) = (lo + span * self.window.0, lo + span * self.window.1);
+        let step = (hi - lo) / (MAX_TICKS - 2) as f64;
+        ([lo, hi], (0..MAX_TICKS - 1).map(|i| self.label(lo + step * i as f64, step)).collect())
+    }
+
+    fn label(&self, v: f64, step: f64) -> String {
+        if self.log {
+            // three significant digits are plenty between decades
+            let v = 10f64.powf(v);
+            let unit = 10f64.powf(v.log10().floor() - 2.0);
+            return format_value((v / unit).round() * unit);
+        }
+        // as many decimals as the tick spacing needs
+        let decimals = (-step.log10().floor()).clamp(0.0, 6.0) as usize;
+        format!("{:.*}", decimals, v)
+    }
+
+    fn zoom(&mut self, factor: f64) {
+        let (w0, w1) = self.window;
+        let center = (w0 + w1) / 2.0;
+        let half = (w1 - w0) * factor / 2.0;
+        self.window = (center - half, center + half);
+    }
+
+    fn pan(&mut self, fraction: f64) {
+        let shift = (self.window.1 - self.window.0) * fraction;
+        self.window = (self.window.0 + shift, self.window.1 + shift);
+    }
+}
+
+/// Shared viewport of the Visualization tab charts.
+#[derive(Clone, Debug)]
+pub struct ChartView {
+    pub x: AxisView,
+    pub y: AxisView,
+}
+
+impl ChartView {
+    pub fn new() -> ChartView {
+        ChartView { x: AxisView::new(), y: AxisView::new() }
+    }
+
+    fn axes(&mut self, axis: Option<Axis>) -> Vec<&mut AxisView> {
+        match axis {
+            Some(Axis::X) => vec![&mut self.x],
+            Some(Axis::Y) => vec![&mut self.y],
+            None => vec![&mut self.x, &mut self.y],
+        }
+    }
+
+    /// Zooms in on one axis, or both without one.
+    pub fn zoom_in(&mut self, axis: Option<Axis>) {
+        self.axes(axis).into_iter().for_each(|a| a.zoom(ZOOM_STEP));
+    }
+
+    pub fn zoom_out(&mut self, axis: Option<Axis>) {
+        self.axes(axis).into_iter().for_each(|a| a.zoom(1.0 / ZOOM_STEP));
+    }
+
+    /// Moves the window by a tenth of its width, `direction` being 1 or -1.
+    pub fn pan(&mut self, axis: Axis, direction: f64) {
+        self.axes(Some(axis)).into_iter().for_each(|a| a.pan(PAN_STEP * direction));
+    }
+
+    /// Back to the whole data range, keeping the scale.
+    pub fn reset(&mut self) {
+        for axis in self.axes(None) {
+            axis.window = (0.0, 1.0);
+            axis.manual = None;
+        }
+    }
+
+    /// Applies the log scale of the axes to the points, dropping those that
+    /// cannot be drawn on it.
+    pub fn transform(&self, points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        points
+            .iter()
+            .filter_map(|(x, y)| Some((self.x.scale(*x)?, self.y.scale(*y)?)))
+            .collect()
+    }
+
+    /// Runs an axes command: `x <min> <max>`, `y <min> <max>`, `log [x|y]`
+    /// to switch an axis (y by default) between linear and log scale, or `fit`.
+    pub fn command(&mut self, input: &str) -> Result<String, String> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let number = |w: &str| w.parse::<f64>().map_err(|_| format!("not a number: {}", w));
+        match words.as_slice() {
+            ["fit"] => {
+                self.reset();
+                Ok("axes fitted to the data".to_string())
+            }
+            ["log"] | ["log", "y"] | ["log", "x"] => {
+                let axis = if words.get(1) == Some(&"x") { &mut self.x } else { &mut self.y };
+                axis.log = !axis.log;
+                axis.window = (0.0, 1.0);
+                Ok(format!("{} scale", if axis.log { "log" } else { "linear" }))
+            }
+            [name @ ("x" | "y"), min, max] => {
+                let (min, max) = (number(min)?, number(max)?);
+                if min >= max {
+                    return Err("the minimum has to be below the maximum".to_string());
+                }
+                let axis = if *name == "x" { &mut self.x } else { &mut self.y };
+                axis.manual = Some((min, max));
+                axis.window = (0.0, 1.0);
+                Ok(format!("{} axis from {} to {}", name, min, max))
+            }
+            _ => Err("usage: x|y <min> <max>, log [x|y] or fit".to_string()),
+        }
+    }
+}
+
+/// Smallest 1, 2 or 5 times a power of ten not below `raw`.
+fn nice_step(raw: f64) -> f64 {
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let fraction = raw / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Lowest and highest x and y of the points.
+pub fn fit(points: impl Iterator<Item = (f64, f64)> + Clone) -> Option<((f64, f64), (f64, f64))> {
+    let x0 = points.clone().map(|p| p.0).reduce(f64::min)?;
+    let x1 = points.clone().map(|p| p.0).reduce(f64::max)?;
+    let y0 = points.clone().map(|p| p.1).reduce(f64::min)?;
+    let y1 = points.map(|p| p.1).reduce(f64::max)?;
+    Some(((x0, x1), (y0, y1)))
+}
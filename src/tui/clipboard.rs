@@ -0,0 +1,65 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use csv::{StringRecord, WriterBuilder};
+use std::io::{self, Write};
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum YankFormat {
+    Csv,
+    Tsv,
+}
+
+impl YankFormat {
+    pub fn delimiter(&self) -> u8 {
+        match self {
+            YankFormat::Csv => b',',
+            YankFormat::Tsv => b'\t',
+        }
+    }
+
+    pub fn toggle(&self) -> YankFormat {
+        match self {
+            YankFormat::Csv => YankFormat::Tsv,
+            YankFormat::Tsv => YankFormat::Csv,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            YankFormat::Csv => "CSV",
+            YankFormat::Tsv => "TSV",
+        }
+    }
+}
+
+pub fn format_records(records: &[StringRecord], format: YankFormat) -> String {
+    let mut wtr = WriterBuilder::new()
+        .delimiter(format.delimiter())
+        .from_writer(vec![]);
+    for record in records {
+        // writing into a Vec cannot fail
+        let _ = wtr.write_record(record);
+    }
+    let bytes = wtr.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).trim_end_matches('\n').to_string()
+}
+
+/// Builds the OSC 52 sequence that asks the terminal to put `text` on the
+/// system clipboard. Inside tmux and screen the sequence has to be wrapped in
+/// a DCS passthrough, otherwise the multiplexer swallows it.
+pub fn osc52_sequence(text: &str) -> String {
+    let osc = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", osc.replace('\x1b', "\x1b\x1b"))
+    } else if std::env::var("TERM").map(|t| t.starts_with("screen")).unwrap_or(false) {
+        format!("\x1bP{}\x1b\\", osc)
+    } else {
+        osc
+    }
+}
+
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
@@ -0,0 +1,236 @@
+use csv::{StringRecord, Writer};
+use std::error::Error;
+use std::path::Path;
+use crate::tui::loaders::Dataset;
+use crate::tui::models::{self, App};
+use crate::tui::session::{self, Session};
+
+// names tried in turn for the source column when none is given
+const SOURCE: &str = "source";
+const SOURCE_FILE: &str = "source_file";
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    // every column of every file, missing cells left empty
+    Union,
+    // only the columns all files have
+    Intersection,
+}
+
+/// How a file's columns differ from the stacked table's.
+pub struct Mismatch {
+    pub file: String,
+    // columns the file lacks, its cells are empty
+    pub missing: Vec<String>,
+    // columns of the file that were left out
+    pub dropped: Vec<String>,
+}
+
+impl Mismatch {
+    pub fn describe(&self) -> String {
+        let mut parts = vec![];
+        if !self.missing.is_empty() {
+            parts.push(format!("missing {}", self.missing.join(", ")));
+        }
+        if !self.dropped.is_empty() {
+            parts.push(format!("dropped {}", self.dropped.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+pub struct Concat {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Stacks the files, matching columns by name. With `source` every row
+/// tells which file it came from in a column of that name.
+pub fn concat(files: &[(String, Dataset)], align: Align, source: Option<&str>) -> Concat {
+    let mut headers: Vec<String> = vec![];
+    for (_, dataset) in files {
+        for h in &dataset.headers {
+            if !headers.contains(h) {
+                headers.push(h.clone());
+            }
+        }
+    }
+    if align == Align::Intersection {
+        headers.retain(|h| files.iter().all(|(_, d)| d.headers.contains(h)));
+    }
+
+    let mut records = vec![];
+    let mut mismatches = vec![];
+    for (path, dataset) in files {
+        let columns: Vec<Option<usize>> = headers.iter().map(|h| dataset.headers.iter().position(|d| d == h)).collect();
+        let mismatch = Mismatch {
+            file: path.clone(),
+            missing: headers.iter().filter(|h| !dataset.headers.contains(h)).cloned().collect(),
+            dropped: dataset.headers.iter().filter(|h| !headers.contains(h)).cloned().collect(),
+        };
+        if !mismatch.missing.is_empty() || !mismatch.dropped.is_empty() {
+            mismatches.push(mismatch);
+        }
+        let name = file_name(path);
+        for record in &dataset.records {
+            let mut row: StringRecord = columns.iter().map(|c| c.and_then(|c| record.get(c)).unwrap_or("")).collect();
+            if source.is_some() {
+                row.push_field(&name);
+            }
+            records.push(row);
+        }
+    }
+    if let Some(column) = source {
+        headers.push(column.to_string());
+    }
+    Concat { headers, records, mismatches }
+}
+
+/// The name of the source column, which must not be one the files already
+/// have. A name given with `--source=` is refused when it is taken, without
+/// one the first free of `source`, `source_file`, `source_file_2`, ... is used.
+fn source_column(files: &[(String, Dataset)], name: Option<&str>) -> Result<String, String> {
+    let taken = |name: &str| files.iter().any(|(_, d)| d.headers.iter().any(|h| h == name));
+    if let Some(name) = name {
+        return match taken(name) {
+            true => Err(format!("the files already have a column {}, name the source column with --source=<column>", name)),
+            false => Ok(name.to_string()),
+        };
+    }
+    let mut candidate = SOURCE.to_string();
+    let mut n = 1;
+    while taken(&candidate) {
+        candidate = match n {
+            1 => SOURCE_FILE.to_string(),
+            n => format!("{}_{}", SOURCE_FILE, n),
+        };
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string())
+}
+
+/// `csv-grep concat <files or patterns> [--intersect] [--source[=column]]
+/// [-o output.csv]` stacks the files and opens the result, or writes it to
+/// the output file. Header mismatches go to the status line and File Info
+/// tab, or to stderr.
+pub fn main(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "usage: csv-grep concat <files> [--intersect] [--source[=column]] [-o output.csv]";
+    let mut patterns = vec![];
+    let mut align = Align::Union;
+    let mut source = None;
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--intersect" => align = Align::Intersection,
+            "--union" => align = Align::Union,
+            "--source" => source = Some(None),
+            "-o" | "--output" => output = Some(args.next().ok_or(usage)?.clone()),
+            _ => match arg.strip_prefix("--source=") {
+                Some(column) => source = Some(Some(column.to_string())),
+                None => patterns.push(arg.clone()),
+            },
+        }
+    }
+    let paths = session::expand(&patterns);
+    if paths.is_empty() {
+        return Err(usage.into());
+    }
+    let files: Vec<(String, Dataset)> = paths
+        .into_iter()
+        .map(|path| {
+            let mut dataset = models::get_attrs(path.clone())?;
+            dataset.load_all()?;
+            Ok((path, dataset))
+        })
+        .collect::<Result<_, String>>()?;
+    let source = match source {
+        Some(name) => Some(source_column(&files, name.as_deref())?),
+        None => None,
+    };
+    let stacked = concat(&files, align, source.as_deref());
+    let report: Vec<String> = stacked.mismatches.iter().map(|m| format!("{}: {}", m.file, m.describe())).collect();
+
+    if let Some(output) = output {
+        let mut wtr = Writer::from_path(&output)?;
+        wtr.write_record(&stacked.headers)?;
+        for record in &stacked.records {
+            wtr.write_record(record)?;
+        }
+        wtr.flush()?;
+        for line in &report {
+            eprintln!("{}", line);
+        }
+        eprintln!("wrote {} row(s) from {} file(s) to {}", stacked.records.len(), files.len(), output);
+        return Ok(());
+    }
+    let name = format!("{} files", files.len());
+    let mut dataset = Dataset::new(&name, "Concatenated CSV", stacked.headers, stacked.records);
+    dataset.info.retain(|(k, _)| k != "Path" && k != "Size");
+    dataset.info.extend(files.iter().map(|(path, d)| (file_name(path), format!("{} rows", d.records.len()))));
+    dataset.info.extend(stacked.mismatches.iter().map(|m| (format!("Mismatch in {}", file_name(&m.file)), m.describe())));
+    let mut app = App::with_dataset(name, dataset);
+    app.watch = None;
+    app.status = match report.len() {
+        0 => format!("stacked {} file(s), all headers match", files.len()),
+        n => format!("stacked {} file(s), {} with other headers: {}", files.len(), n, report.join(" | ")),
+    };
+    crate::tui::main::show(Session::from_app(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, headers: &[&str], rows: &[&[&str]]) -> (String, Dataset) {
+        let records = rows.iter().map(|r| StringRecord::from(r.to_vec())).collect();
+        (path.to_string(), Dataset::new(path, "CSV", headers.iter().map(|h| h.to_string()).collect(), records))
+    }
+
+    fn rows(concat: &Concat) -> Vec<Vec<&str>> {
+        concat.records.iter().map(|r| r.iter().collect()).collect()
+    }
+
+    fn files() -> Vec<(String, Dataset)> {
+        vec![
+            file("data/jan.csv", &["id", "amount"], &[&["1", "10"]]),
+            file("data/feb.csv", &["amount", "id", "note"], &[&["20", "2", "late"]]),
+        ]
+    }
+
+    #[test]
+    fn union_keeps_every_column_in_first_seen_order() {
+        let concat = concat(&files(), Align::Union, None);
+        assert_eq!(concat.headers, vec!["id", "amount", "note"]);
+        assert_eq!(rows(&concat), vec![vec!["1", "10", ""], vec!["2", "20", "late"]]);
+        assert_eq!(concat.mismatches.len(), 1);
+        assert_eq!(concat.mismatches[0].describe(), "missing note");
+    }
+
+    #[test]
+    fn intersection_keeps_only_shared_columns() {
+        let concat = concat(&files(), Align::Intersection, Some("source"));
+        assert_eq!(concat.headers, vec!["id", "amount", "source"]);
+        assert_eq!(rows(&concat), vec![vec!["1", "10", "jan.csv"], vec!["2", "20", "feb.csv"]]);
+        assert_eq!(concat.mismatches[0].file, "data/feb.csv");
+        assert_eq!(concat.mismatches[0].describe(), "dropped note");
+    }
+
+    #[test]
+    fn source_column_takes_a_free_name() {
+        assert_eq!(source_column(&files(), None), Ok("source".to_string()));
+        let mut taken = files();
+        taken.push(file("mar.csv", &["source"], &[]));
+        assert_eq!(source_column(&taken, None), Ok("source_file".to_string()));
+        taken.push(file("apr.csv", &["source_file"], &[]));
+        assert_eq!(source_column(&taken, None), Ok("source_file_2".to_string()));
+        assert!(source_column(&taken, Some("note")).is_err());
+        assert_eq!(source_column(&taken, Some("file")), Ok("file".to_string()));
+    }
+}
@@ -0,0 +1,91 @@
+use csv::StringRecord;
+use crate::tui::stats;
+
+
+/// x label, y label and the points of a column pair.
+pub type Pair = (String, String, Vec<(f64, f64)>);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    Pearson,
+    Spearman,
+}
+
+/// Correlation matrix across the numeric columns. Every pair only uses the
+/// rows where both columns hold a number (pairwise complete).
+pub struct Correlation {
+    pub method: Method,
+    pub labels: Vec<String>,
+    columns: Vec<Vec<Option<f64>>>,
+    pub matrix: Vec<Vec<Option<f64>>>,
+    // (row, column) of the selected cell
+    pub cursor: (usize, usize),
+    pub generation: Option<usize>,
+}
+
+impl Correlation {
+    pub fn new() -> Correlation {
+        Correlation {
+            method: Method::Pearson,
+            labels: vec![],
+            columns: vec![],
+            matrix: vec![],
+            cursor: (0, 1),
+            generation: None,
+        }
+    }
+
+    pub fn compute(&mut self, headers: &[String], records: &[StringRecord], numeric: &[usize]) {
+        self.labels = numeric.iter().map(|c| headers[*c].clone()).collect();
+        self.columns = numeric
+            .iter()
+            .map(|c| records.iter().map(|r| r.get(*c).and_then(|v| v.trim().parse::<f64>().ok())).collect())
+            .collect();
+        let n = self.labels.len();
+        self.matrix = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        let (x, y): (Vec<f64>, Vec<f64>) = self.pair(i, j).into_iter().unzip();
+                        match self.method {
+                            Method::Pearson => stats::pearson(&x, &y),
+                            Method::Spearman => stats::spearman(&x, &y),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        self.cursor = (self.cursor.0.min(n.saturating_sub(1)), self.cursor.1.min(n.saturating_sub(1)));
+    }
+
+    /// Rows where both columns are numbers, as (x, y) points.
+    pub fn pair(&self, i: usize, j: usize) -> Vec<(f64, f64)> {
+        self.columns[i]
+            .iter()
+            .zip(&self.columns[j])
+            .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+            .collect()
+    }
+
+    pub fn selected_pair(&self) -> Option<Pair> {
+        let (i, j) = self.cursor;
+        let (x, y) = (self.labels.get(j)?, self.labels.get(i)?);
+        Some((x.clone(), y.clone(), self.pair(j, i)))
+    }
+
+    pub fn toggle_method(&mut self) {
+        self.method = match self.method {
+            Method::Pearson => Method::Spearman,
+            Method::Spearman => Method::Pearson,
+        };
+        self.generation = None;
+    }
+
+    pub fn move_cursor(&mut self, rows: isize, columns: isize) {
+        let last = self.labels.len().saturating_sub(1) as isize;
+        self.cursor = (
+            (self.cursor.0 as isize + rows).clamp(0, last) as usize,
+            (self.cursor.1 as isize + columns).clamp(0, last) as usize,
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use csv::StringRecord;
+use crate::tui::expr::Expr;
+
+
+/// A column computed row by row from an expression, appended to the table
+/// like any column of the file.
+#[derive(Clone, Debug)]
+pub struct Derived {
+    pub name: String,
+    pub expression: String,
+    expr: Expr,
+    // where the column sits in the table
+    column: usize,
+}
+
+impl Derived {
+    /// Parses `name = expression`, or a bare expression that then names the
+    /// column itself, against the columns there are so far.
+    pub fn parse(input: &str, headers: &[String]) -> Result<Derived, String> {
+        match split_name(input) {
+            Some((name, expression)) => Derived::new(name, expression, headers),
+            None => Derived::new(input.trim().to_string(), input.trim().to_string(), headers),
+        }
+    }
+
+    pub fn new(name: String, expression: String, headers: &[String]) -> Result<Derived, String> {
+        if headers.contains(&name) {
+            return Err(format!("there already is a column {}", name));
+        }
+        let expr = Expr::parse(&expression, headers)?;
+        Ok(Derived { name, expression, expr, column: headers.len() })
+    }
+
+    /// The same column over other headers, e.g. those of a reloaded file.
+    pub fn reparse(&self, headers: &[String]) -> Result<Derived, String> {
+        Derived::new(self.name.clone(), self.expression.clone(), headers)
+    }
+
+    /// Appends the value to every record and returns how many rows it could
+    /// not be computed for; those are left empty. Ragged records are cut or
+    /// padded to the headers first, so the value lands in its own column.
+    pub fn apply(&self, records: &mut [StringRecord]) -> usize {
+        let mut failed = 0;
+        for record in records.iter_mut() {
+            record.truncate(self.column);
+            while record.len() < self.column {
+                record.push_field("");
+            }
+            let value = match self.expr.eval(record) {
+                Ok(value) => value.to_cell(),
+                Err(_) => {
+                    failed += 1;
+                    String::new()
+                }
+            };
+            record.push_field(&value);
+        }
+        failed
+    }
+}
+
+/// Whether the non empty values of a column all parse as numbers.
+pub fn is_numeric(records: &[StringRecord], column: usize) -> bool {
+    let mut values = records.iter().filter_map(|r| r.get(column)).filter(|v| !v.is_empty()).peekable();
+    values.peek().is_some() && values.all(|v| v.trim().parse::<f64>().is_ok())
+}
+
+/// Splits `name = expression` at an `=` that is not part of `==`, `!=`,
+/// `<=` or `>=`, as long as the name is not itself an expression.
+fn split_name(input: &str) -> Option<(String, String)> {
+    let chars: Vec<char> = input.chars().collect();
+    let at = (0..chars.len()).find(|i| {
+        chars[*i] == '='
+            && chars.get(i + 1) != Some(&'=')
+            && !matches!(i.checked_sub(1).map(|j| chars[j]), Some('=' | '!' | '<' | '>'))
+    })?;
+    let name: String = chars[..at].iter().collect::<String>().trim().trim_matches('`').to_string();
+    let expression: String = chars[at + 1..].iter().collect();
+    let is_name = !name.is_empty() && !name.contains(['(', ')', '"', '\'', ',']);
+    is_name.then(|| (name, expression.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ragged_records_get_the_value_in_its_own_column() {
+        let headers = vec!["a".to_string(), "b".to_string()];
+        let derived = Derived::parse("c = a * 2", &headers).unwrap();
+        let mut records = vec![
+            StringRecord::from(vec!["1", "x"]),
+            StringRecord::from(vec!["2"]),
+            StringRecord::from(vec!["3", "y", "extra"]),
+        ];
+        assert_eq!(derived.apply(&mut records), 0);
+        let rows: Vec<Vec<&str>> = records.iter().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec![vec!["1", "x", "2"], vec!["2", "", "4"], vec!["3", "y", "6"]]);
+    }
+}
@@ -0,0 +1,270 @@
+use csv::{StringRecord, Writer};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use crate::tui::loaders::Dataset;
+use crate::tui::models::{self, App};
+use crate::tui::session::Session;
+
+// joins the old and the new value of a changed cell
+const ARROW: &str = " → ";
+
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    // positions in `Diff::headers` of the cells that differ
+    Changed(Vec<usize>),
+}
+
+impl Change {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed(_) => "changed",
+        }
+    }
+}
+
+/// The rows that differ between two versions of a file, matched on key
+/// columns. Columns are matched by name; only those in both files are
+/// compared.
+pub struct Diff {
+    // "change" and the columns of both files, old ones first
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    pub changes: Vec<Change>,
+    pub unchanged: usize,
+    pub columns_added: Vec<String>,
+    pub columns_removed: Vec<String>,
+}
+
+impl Diff {
+    /// Compares `old` with `new` on `keys` (column names), or on the row
+    /// number without keys. A key found several times is matched by
+    /// occurrence.
+    pub fn compare(old: &Dataset, new: &Dataset, keys: &[String]) -> Result<Diff, String> {
+        let key_columns = |dataset: &Dataset, which: &str| {
+            keys.iter()
+                .map(|k| dataset.headers.iter().position(|h| h == k).ok_or(format!("no column {} in the {} file", k, which)))
+                .collect::<Result<Vec<usize>, String>>()
+        };
+        let (old_keys, new_keys) = (key_columns(old, "old")?, key_columns(new, "new")?);
+        let key = |record: &StringRecord, columns: &[usize], row: usize| -> Vec<String> {
+            match columns.is_empty() {
+                true => vec![row.to_string()],
+                false => columns.iter().map(|c| record.get(*c).unwrap_or("").to_string()).collect(),
+            }
+        };
+
+        let mut headers = old.headers.clone();
+        headers.extend(new.headers.iter().filter(|h| !old.headers.contains(h)).cloned());
+        let position = |dataset: &Dataset| -> Vec<Option<usize>> {
+            headers.iter().map(|h| dataset.headers.iter().position(|d| d == h)).collect()
+        };
+        let (in_old, in_new) = (position(old), position(new));
+
+        let mut new_rows: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+        for (i, record) in new.records.iter().enumerate() {
+            new_rows.entry(key(record, &new_keys, i)).or_default().push(i);
+        }
+        for rows in new_rows.values_mut() {
+            rows.reverse();
+        }
+
+        let mut diff = Diff {
+            headers: std::iter::once("change".to_string()).chain(headers.iter().cloned()).collect(),
+            records: vec![],
+            changes: vec![],
+            unchanged: 0,
+            columns_added: new.headers.iter().filter(|h| !old.headers.contains(h)).cloned().collect(),
+            columns_removed: old.headers.iter().filter(|h| !new.headers.contains(h)).cloned().collect(),
+        };
+        let cell = |record: &StringRecord, at: Option<usize>| at.and_then(|c| record.get(c)).unwrap_or("").to_string();
+        let mut matched = vec![false; new.records.len()];
+        for (i, record) in old.records.iter().enumerate() {
+            let Some(j) = new_rows.get_mut(&key(record, &old_keys, i)).and_then(|rows| rows.pop()) else {
+                let cells = in_old.iter().map(|c| cell(record, *c));
+                diff.push(Change::Removed, cells.collect());
+                continue;
+            };
+            matched[j] = true;
+            let other = &new.records[j];
+            let mut changed = vec![];
+            let cells: Vec<String> = in_old
+                .iter()
+                .zip(&in_new)
+                .enumerate()
+                .map(|(c, (o, n))| match (o, n) {
+                    (Some(_), Some(_)) if cell(record, *o) != cell(other, *n) => {
+                        changed.push(c + 1);
+                        format!("{}{}{}", cell(record, *o), ARROW, cell(other, *n))
+                    }
+                    (Some(_), _) => cell(record, *o),
+                    _ => cell(other, *n),
+                })
+                .collect();
+            match changed.is_empty() {
+                true => diff.unchanged += 1,
+                false => diff.push(Change::Changed(changed), cells),
+            }
+        }
+        for (record, _) in new.records.iter().zip(&matched).filter(|(_, m)| !**m) {
+            let cells = in_new.iter().map(|c| cell(record, *c));
+            diff.push(Change::Added, cells.collect());
+        }
+        Ok(diff)
+    }
+
+    fn push(&mut self, change: Change, cells: Vec<String>) {
+        let mut record = StringRecord::from(vec![change.name()]);
+        record.extend(cells);
+        self.records.push(record);
+        self.changes.push(change);
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.changes.iter().filter(|c| c.name() == name).count()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} added, {} removed, {} changed, {} unchanged",
+            self.count("added"),
+            self.count("removed"),
+            self.count("changed"),
+            self.unchanged,
+        );
+        if !self.columns_added.is_empty() {
+            summary.push_str(&format!(" | new columns: {}", self.columns_added.join(", ")));
+        }
+        if !self.columns_removed.is_empty() {
+            summary.push_str(&format!(" | dropped columns: {}", self.columns_removed.join(", ")));
+        }
+        summary
+    }
+
+    /// The differing rows as a dataset for the Data Explorer.
+    fn dataset(&self, old_path: &str, new_path: &str) -> Dataset {
+        let mut dataset = Dataset::new(new_path, "CSV diff", self.headers.clone(), self.records.clone());
+        dataset.info.retain(|(k, _)| k != "Path" && k != "Size");
+        dataset.info.insert(1, ("Old".to_string(), old_path.to_string()));
+        dataset.info.insert(2, ("New".to_string(), new_path.to_string()));
+        for name in ["added", "removed", "changed"] {
+            dataset.info.push((name.to_string(), self.count(name).to_string()));
+        }
+        dataset.info.push(("unchanged".to_string(), self.unchanged.to_string()));
+        dataset
+    }
+}
+
+/// `csv-grep diff <old> <new> [--key a,b] [--print]`. Shows the differences
+/// in the Data Explorer, or with `--print` writes them as CSV to stdout and
+/// the summary to stderr, exiting with 1 when the files differ, e.g. for a
+/// CI job.
+pub fn main(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut paths = vec![];
+    let mut keys: Vec<String> = vec![];
+    let mut print = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--key" | "-k" => {
+                let columns = args.next().ok_or("--key needs column names, e.g. --key id")?;
+                keys.extend(columns.split(',').map(|k| k.trim().to_string()));
+            }
+            "--print" => print = true,
+            _ => paths.push(arg.clone()),
+        }
+    }
+    let [old_path, new_path] = paths.as_slice() else {
+        return Err("usage: csv-grep diff <old> <new> [--key column[,column]] [--print]".into());
+    };
+    let (mut old, mut new) = (models::get_attrs(old_path.clone())?, models::get_attrs(new_path.clone())?);
+    old.load_all()?;
+    new.load_all()?;
+    let diff = Diff::compare(&old, &new, &keys)?;
+
+    if print {
+        let mut wtr = Writer::from_writer(io::stdout());
+        wtr.write_record(&diff.headers)?;
+        for record in &diff.records {
+            wtr.write_record(record)?;
+        }
+        wtr.flush()?;
+        eprintln!("{}", diff.summary());
+        if !diff.records.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let mut app = App::with_dataset(new_path.clone(), diff.dataset(old_path, new_path));
+    // the diff is a snapshot, reloading would replace it with the new file
+    app.watch = None;
+    app.status = diff.summary();
+    app.diff = Some(diff);
+    crate::tui::main::show(Session::from_app(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(headers: &[&str], rows: &[&[&str]]) -> Dataset {
+        let records = rows.iter().map(|r| StringRecord::from(r.to_vec())).collect();
+        Dataset::new("test.csv", "CSV", headers.iter().map(|h| h.to_string()).collect(), records)
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    fn rows(diff: &Diff) -> Vec<Vec<&str>> {
+        diff.records.iter().map(|r| r.iter().collect()).collect()
+    }
+
+    #[test]
+    fn rows_are_matched_on_the_key_wherever_they_are() {
+        let old = dataset(&["id", "name"], &[&["1", "Ann"], &["2", "Bob"], &["3", "Cy"]]);
+        let new = dataset(&["id", "name"], &[&["3", "Cy"], &["4", "Dee"], &["1", "Anne"]]);
+        let diff = Diff::compare(&old, &new, &keys(&["id"])).unwrap();
+        assert_eq!(rows(&diff), vec![
+            vec!["changed", "1", "Ann → Anne"],
+            vec!["removed", "2", "Bob"],
+            vec!["added", "4", "Dee"],
+        ]);
+        assert_eq!(diff.changes[0], Change::Changed(vec![2]));
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn several_key_columns_and_repeated_keys_match_in_order() {
+        let old = dataset(&["day", "shop", "n"], &[&["mon", "a", "1"], &["mon", "b", "2"], &["mon", "a", "3"]]);
+        let new = dataset(&["day", "shop", "n"], &[&["mon", "a", "1"], &["mon", "a", "4"], &["mon", "b", "2"]]);
+        let diff = Diff::compare(&old, &new, &keys(&["day", "shop"])).unwrap();
+        assert_eq!(rows(&diff), vec![vec!["changed", "mon", "a", "3 → 4"]]);
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn without_keys_rows_are_matched_by_position() {
+        let old = dataset(&["name"], &[&["Ann"], &["Bob"]]);
+        let new = dataset(&["name"], &[&["Bob"], &["Ann"], &["Cy"]]);
+        let diff = Diff::compare(&old, &new, &[]).unwrap();
+        assert_eq!(diff.count("changed"), 2);
+        assert_eq!(diff.count("added"), 1);
+    }
+
+    #[test]
+    fn columns_only_one_file_has_are_reported_not_compared() {
+        let old = dataset(&["id", "old"], &[&["1", "x"]]);
+        let new = dataset(&["id", "new"], &[&["1", "y"]]);
+        let diff = Diff::compare(&old, &new, &keys(&["id"])).unwrap();
+        assert_eq!(diff.headers, vec!["change", "id", "old", "new"]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!((diff.columns_added.clone(), diff.columns_removed.clone()), (keys(&["new"]), keys(&["old"])));
+        assert_eq!(Diff::compare(&old, &new, &keys(&["old"])).err(), Some("no column old in the new file".to_string()));
+    }
+}
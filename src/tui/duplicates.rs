@@ -0,0 +1,110 @@
+use csv::StringRecord;
+use std::collections::HashMap;
+use strum::{Display, EnumString};
+
+
+/// Which occurrence of a duplicated row survives deduplication.
+#[derive(Clone, Copy, Debug, PartialEq, Display, EnumString)]
+pub enum Keep {
+    #[strum(to_string = "first")]
+    First,
+    #[strum(to_string = "last")]
+    Last,
+}
+
+/// Rows sharing the same key, in file order.
+pub struct DuplicateGroup {
+    pub rows: Vec<usize>,
+}
+
+/// The key of a record: the `keys` columns, or the whole row without any.
+fn key(record: &StringRecord, keys: &[usize]) -> Vec<String> {
+    match keys.is_empty() {
+        true => record.iter().map(|f| f.to_string()).collect(),
+        false => keys.iter().map(|k| record.get(*k).unwrap_or("").to_string()).collect(),
+    }
+}
+
+/// Groups of two or more rows with the same key, ordered by their first row.
+pub fn find(records: &[StringRecord], keys: &[usize]) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = vec![];
+    let mut index: HashMap<Vec<String>, usize> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        let key = key(record, keys);
+        match index.get(&key) {
+            Some(g) => groups[*g].rows.push(i),
+            None => {
+                index.insert(key, groups.len());
+                groups.push(DuplicateGroup { rows: vec![i] });
+            }
+        }
+    }
+    groups.retain(|g| g.rows.len() > 1);
+    groups
+}
+
+/// Positions of the rows left after dropping duplicates, in file order.
+pub fn dedup(records: &[StringRecord], keys: &[usize], keep: Keep) -> Vec<usize> {
+    let mut kept: HashMap<Vec<String>, usize> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        let key = key(record, keys);
+        match keep {
+            Keep::First => {
+                kept.entry(key).or_insert(i);
+            }
+            Keep::Last => {
+                kept.insert(key, i);
+            }
+        }
+    }
+    let mut rows: Vec<usize> = kept.into_values().collect();
+    rows.sort_unstable();
+    rows
+}
+
+/// Parses the comma separated key columns typed into the prompt, no columns
+/// meaning whole rows.
+pub fn parse_keys(input: &str, headers: &[String]) -> Result<Vec<usize>, String> {
+    input
+        .split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| headers.iter().position(|h| h == name).ok_or_else(|| format!("unknown column {}", name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<StringRecord> {
+        [["1", "Ann"], ["2", "Bob"], ["1", "Ann"], ["3", "Bob"], ["1", "Ann"]]
+            .iter()
+            .map(|r| StringRecord::from(r.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn whole_rows_or_key_columns_make_the_key() {
+        let groups: Vec<Vec<usize>> = find(&records(), &[]).into_iter().map(|g| g.rows).collect();
+        assert_eq!(groups, vec![vec![0, 2, 4]]);
+        let groups: Vec<Vec<usize>> = find(&records(), &[1]).into_iter().map(|g| g.rows).collect();
+        assert_eq!(groups, vec![vec![0, 2, 4], vec![1, 3]]);
+    }
+
+    #[test]
+    fn dedup_keeps_the_first_or_last_occurrence_in_file_order() {
+        assert_eq!(dedup(&records(), &[], Keep::First), vec![0, 1, 3]);
+        assert_eq!(dedup(&records(), &[], Keep::Last), vec![1, 3, 4]);
+        assert_eq!(dedup(&records(), &[1], Keep::First), vec![0, 1]);
+        assert_eq!(dedup(&records(), &[1], Keep::Last), vec![3, 4]);
+    }
+
+    #[test]
+    fn keys_are_column_names() {
+        let headers = vec!["id".to_string(), "name".to_string()];
+        assert_eq!(parse_keys(" name, id ", &headers), Ok(vec![1, 0]));
+        assert_eq!(parse_keys("", &headers), Ok(vec![]));
+        assert_eq!(parse_keys("age", &headers), Err("unknown column age".to_string()));
+    }
+}
@@ -0,0 +1,681 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use csv::StringRecord;
+use std::cmp::Ordering;
+use crate::tui::{quality, time_series};
+
+
+/// A cell or the result of an expression. Cells are numbers when they parse
+/// as one and text otherwise, empty cells are null.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Number(f64),
+    Text(String),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn from_cell(cell: &str) -> Value {
+        match cell.trim().parse::<f64>() {
+            _ if cell.is_empty() => Value::Null,
+            Ok(n) => Value::Number(n),
+            Err(_) => Value::Text(cell.to_string()),
+        }
+    }
+
+    pub fn to_cell(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Number(n) => format_number(*n),
+            Value::Text(t) => t.clone(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    fn number(&self) -> Result<Option<f64>, String> {
+        match self {
+            Value::Null => Ok(None),
+            Value::Number(n) => Ok(Some(*n)),
+            Value::Bool(b) => Ok(Some(if *b { 1.0 } else { 0.0 })),
+            Value::Text(t) => t.trim().parse::<f64>().map(Some).map_err(|_| format!("{} is not a number", t)),
+        }
+    }
+
+    pub fn truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Number(n) => *n != 0.0,
+            Value::Bool(b) => *b,
+            Value::Text(t) => !t.is_empty() && !t.eq_ignore_ascii_case("false"),
+        }
+    }
+
+    fn date(&self) -> Result<Option<NaiveDate>, String> {
+        let text = match self {
+            Value::Null => return Ok(None),
+            v => v.to_cell(),
+        };
+        parse_date(&text).map(Some).ok_or(format!("{} is not a date", text))
+    }
+
+    /// Numbers by value and anything else as text; there is no order
+    /// between null and any other value, null itself included.
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self.number(), other.number()) {
+            _ if *self == Value::Null || *other == Value::Null => None,
+            (Ok(Some(a)), Ok(Some(b))) => Some(a.total_cmp(&b)),
+            _ => Some(self.to_cell().cmp(&other.to_cell())),
+        }
+    }
+}
+
+/// A number as stored in a cell: the shortest form that reads back as the
+/// same number, without a trailing `.0` for whole numbers.
+pub fn format_number(n: f64) -> String {
+    match n {
+        // -0 is written as 0
+        0.0 => "0".to_string(),
+        n => format!("{}", n),
+    }
+}
+
+/// Reads a date in any of the formats the data quality and time series
+/// tabs recognise.
+pub fn parse_date(text: &str) -> Option<NaiveDate> {
+    quality::DATE_FORMATS
+        .iter()
+        .chain(time_series::DATETIME_FORMATS.iter())
+        .find_map(|f| time_series::parse(text, f))
+        .map(|t| t.date())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl Op {
+    // how tightly the operator binds, higher first
+    fn precedence(&self) -> u8 {
+        match self {
+            Op::Or => 1,
+            Op::And => 2,
+            Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge => 3,
+            Op::Add | Op::Sub => 4,
+            Op::Mul | Op::Div | Op::Rem => 5,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Rem => "%",
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::And => "and",
+            Op::Or => "or",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Name(String),
+    Op(Op),
+    Not,
+    Open,
+    Close,
+    Comma,
+}
+
+impl Token {
+    // as typed, for error messages
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(n) => format_number(*n),
+            Token::Text(t) => format!("\"{}\"", t),
+            Token::Name(name) => name.clone(),
+            Token::Op(op) => op.symbol().to_string(),
+            Token::Not => "!".to_string(),
+            Token::Open => "(".to_string(),
+            Token::Close => ")".to_string(),
+            Token::Comma => ",".to_string(),
+        }
+    }
+}
+
+/// A parsed expression over the columns of a row, e.g. `amount * 1.19`,
+/// `upper(state)` or `concat(name/first, " ", name/last)`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Column(usize),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+pub const FUNCTIONS: [&str; 37] = [
+    "abs", "round", "floor", "ceil", "sqrt", "pow", "ln", "log10", "exp", "min", "max",
+    "upper", "lower", "trim", "len", "concat", "substr", "replace", "contains", "starts_with", "ends_with", "left", "right",
+    "date", "year", "month", "day", "weekday", "days", "add_days", "today",
+    "if", "coalesce", "is_null", "number", "text", "not",
+];
+
+impl Expr {
+    /// Parses `input`; names are columns of `headers`, which may contain `/`
+    /// as in `name/first`, and names with spaces are quoted in backticks.
+    pub fn parse(input: &str, headers: &[String]) -> Result<Expr, String> {
+        let tokens = tokenize(input, headers)?;
+        let mut parser = Parser { tokens, position: 0, headers };
+        let expr = parser.expression(0)?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected {}", token.describe())),
+        }
+    }
+
+    pub fn eval(&self, record: &StringRecord) -> Result<Value, String> {
+        match self {
+            Expr::Literal(v) => Ok(v.clone()),
+            Expr::Column(c) => Ok(Value::from_cell(record.get(*c).unwrap_or(""))),
+            Expr::Negate(e) => Ok(e.eval(record)?.number()?.map(|n| Value::Number(-n)).unwrap_or(Value::Null)),
+            Expr::Not(e) => Ok(Value::Bool(!e.eval(record)?.truthy())),
+            Expr::Binary(op, a, b) => binary(*op, a.eval(record)?, b, record),
+            Expr::Call(name, args) => call(name, args, record),
+        }
+    }
+}
+
+fn binary(op: Op, a: Value, b: &Expr, record: &StringRecord) -> Result<Value, String> {
+    // and/or only look at the right side when they need to
+    match op {
+        Op::And if !a.truthy() => return Ok(Value::Bool(false)),
+        Op::Or if a.truthy() => return Ok(Value::Bool(true)),
+        Op::And | Op::Or => return Ok(Value::Bool(b.eval(record)?.truthy())),
+        _ => {}
+    }
+    let b = b.eval(record)?;
+    // null only equals null and is neither less nor greater than anything
+    let comparison = |ordering: fn(Ordering) -> bool| Ok(a.compare(&b).map_or(Value::Null, |o| Value::Bool(ordering(o))));
+    match op {
+        Op::Eq | Op::Ne if a == Value::Null || b == Value::Null => {
+            Ok(Value::Bool((a == b) == (op == Op::Eq)))
+        }
+        Op::Eq => comparison(|o| o == Ordering::Equal),
+        Op::Ne => comparison(|o| o != Ordering::Equal),
+        Op::Lt => comparison(|o| o == Ordering::Less),
+        Op::Le => comparison(|o| o != Ordering::Greater),
+        Op::Gt => comparison(|o| o == Ordering::Greater),
+        Op::Ge => comparison(|o| o != Ordering::Less),
+        _ => {
+            let (Some(x), Some(y)) = (a.number()?, b.number()?) else { return Ok(Value::Null) };
+            Ok(Value::Number(match op {
+                Op::Add => x + y,
+                Op::Sub => x - y,
+                Op::Mul => x * y,
+                Op::Div if y == 0.0 => return Err("division by zero".to_string()),
+                Op::Div => x / y,
+                _ => x % y,
+            }))
+        }
+    }
+}
+
+fn call(name: &str, args: &[Expr], record: &StringRecord) -> Result<Value, String> {
+    // conditionals evaluate only the branches they need
+    match name {
+        "if" => {
+            let [condition, then, otherwise] = args else { return Err("if(condition, then, else)".to_string()) };
+            return match condition.eval(record)?.truthy() {
+                true => then.eval(record),
+                false => otherwise.eval(record),
+            };
+        }
+        "coalesce" => {
+            for arg in args {
+                let value = arg.eval(record)?;
+                if value != Value::Null {
+                    return Ok(value);
+                }
+            }
+            return Ok(Value::Null);
+        }
+        _ => {}
+    }
+    let values = args.iter().map(|a| a.eval(record)).collect::<Result<Vec<Value>, String>>()?;
+    let arity = |n: usize| match values.len() == n {
+        true => Ok(()),
+        false => Err(format!("{} takes {} argument(s)", name, n)),
+    };
+    let number = |i: usize| values[i].number();
+    let text = |i: usize| values[i].to_cell();
+    let math = |f: fn(f64) -> f64| -> Result<Value, String> {
+        arity(1)?;
+        Ok(number(0)?.map(|x| Value::Number(f(x))).unwrap_or(Value::Null))
+    };
+    let string = |f: fn(&str) -> String| -> Result<Value, String> {
+        arity(1)?;
+        Ok(match &values[0] {
+            Value::Null => Value::Null,
+            v => Value::Text(f(&v.to_cell())),
+        })
+    };
+    let date_part = |f: fn(NaiveDate) -> f64| -> Result<Value, String> {
+        arity(1)?;
+        Ok(values[0].date()?.map(|d| Value::Number(f(d))).unwrap_or(Value::Null))
+    };
+    match name {
+        "abs" => math(f64::abs),
+        "floor" => math(f64::floor),
+        "ceil" => math(f64::ceil),
+        "sqrt" => math(f64::sqrt),
+        "ln" => math(f64::ln),
+        "log10" => math(f64::log10),
+        "exp" => math(f64::exp),
+        "round" => {
+            let digits = match values.len() {
+                1 => 0.0,
+                2 => number(1)?.unwrap_or(0.0),
+                _ => return Err("round(x[, digits])".to_string()),
+            };
+            let scale = 10f64.powi(digits as i32);
+            Ok(number(0)?.map(|x| Value::Number((x * scale).round() / scale)).unwrap_or(Value::Null))
+        }
+        "pow" => {
+            arity(2)?;
+            let (Some(x), Some(y)) = (number(0)?, number(1)?) else { return Ok(Value::Null) };
+            Ok(Value::Number(x.powf(y)))
+        }
+        "min" | "max" => {
+            let present = values.iter().filter(|v| **v != Value::Null);
+            let best = match name {
+                "min" => present.min_by(|a, b| a.compare(b).unwrap_or(Ordering::Equal)),
+                _ => present.max_by(|a, b| a.compare(b).unwrap_or(Ordering::Equal)),
+            };
+            Ok(best.cloned().unwrap_or(Value::Null))
+        }
+        "upper" => string(str::to_uppercase),
+        "lower" => string(str::to_lowercase),
+        "trim" => string(|s| s.trim().to_string()),
+        "len" => {
+            arity(1)?;
+            Ok(Value::Number(text(0).chars().count() as f64))
+        }
+        "concat" => Ok(Value::Text(values.iter().map(|v| v.to_cell()).collect())),
+        "substr" => {
+            if !(2..=3).contains(&values.len()) {
+                return Err("substr(text, start[, length])".to_string());
+            }
+            // 1-based like SQL's
+            let start = number(1)?.unwrap_or(1.0).max(1.0) as usize - 1;
+            let value = text(0);
+            let chars = value.chars().skip(start);
+            Ok(Value::Text(match values.len() {
+                2 => chars.collect(),
+                _ => chars.take(number(2)?.unwrap_or(0.0).max(0.0) as usize).collect(),
+            }))
+        }
+        "left" | "right" => {
+            arity(2)?;
+            let n = number(1)?.unwrap_or(0.0).max(0.0) as usize;
+            let chars: Vec<char> = text(0).chars().collect();
+            let part = match name {
+                "left" => &chars[..n.min(chars.len())],
+                _ => &chars[chars.len().saturating_sub(n)..],
+            };
+            Ok(Value::Text(part.iter().collect()))
+        }
+        "replace" => {
+            arity(3)?;
+            Ok(Value::Text(text(0).replace(&text(1), &text(2))))
+        }
+        "contains" => {
+            arity(2)?;
+            Ok(Value::Bool(text(0).contains(&text(1))))
+        }
+        "starts_with" => {
+            arity(2)?;
+            Ok(Value::Bool(text(0).starts_with(&text(1))))
+        }
+        "ends_with" => {
+            arity(2)?;
+            Ok(Value::Bool(text(0).ends_with(&text(1))))
+        }
+        "date" => {
+            arity(1)?;
+            Ok(values[0].date()?.map(|d| Value::Text(d.format("%Y-%m-%d").to_string())).unwrap_or(Value::Null))
+        }
+        "year" => date_part(|d| d.year() as f64),
+        "month" => date_part(|d| d.month() as f64),
+        "day" => date_part(|d| d.day() as f64),
+        // 1 for Monday up to 7 for Sunday
+        "weekday" => date_part(|d| d.weekday().number_from_monday() as f64),
+        "days" => {
+            arity(2)?;
+            let (Some(from), Some(to)) = (values[0].date()?, values[1].date()?) else { return Ok(Value::Null) };
+            Ok(Value::Number((to - from).num_days() as f64))
+        }
+        "add_days" => {
+            arity(2)?;
+            let (Some(date), Some(n)) = (values[0].date()?, number(1)?) else { return Ok(Value::Null) };
+            Ok(Value::Text((date + Duration::days(n as i64)).format("%Y-%m-%d").to_string()))
+        }
+        "today" => {
+            arity(0)?;
+            Ok(Value::Text(Local::now().date_naive().format("%Y-%m-%d").to_string()))
+        }
+        "is_null" => {
+            arity(1)?;
+            Ok(Value::Bool(values[0] == Value::Null))
+        }
+        "not" => {
+            arity(1)?;
+            Ok(Value::Bool(!values[0].truthy()))
+        }
+        "number" => {
+            arity(1)?;
+            Ok(number(0)?.map(Value::Number).unwrap_or(Value::Null))
+        }
+        "text" => {
+            arity(1)?;
+            Ok(Value::Text(text(0)))
+        }
+        _ => Err(format!("unknown function {}", name)),
+    }
+}
+
+fn tokenize(input: &str, headers: &[String]) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    let is_name = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, length) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            (c, _) if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let end = (i..chars.len()).find(|j| !(chars[*j].is_ascii_digit() || chars[*j] == '.')).unwrap_or(chars.len());
+                let text: String = chars[i..end].iter().collect();
+                let number = text.parse::<f64>().map_err(|_| format!("not a number: {}", text))?;
+                (Token::Number(number), end - i)
+            }
+            ('"' | '\'' | '`', _) => {
+                let end = (i + 1..chars.len()).find(|j| chars[*j] == c).ok_or("unterminated quote")?;
+                let text: String = chars[i + 1..end].iter().collect();
+                let token = match c {
+                    '`' => Token::Name(text),
+                    _ => Token::Text(text),
+                };
+                (token, end + 1 - i)
+            }
+            (c, _) if is_name(c) => {
+                let mut end = (i..chars.len()).find(|j| !is_name(chars[*j])).unwrap_or(chars.len());
+                // `a/b` is a column when the file has one by that name (or
+                // starting with it, for deeper nesting), a division otherwise
+                while chars.get(end) == Some(&'/') {
+                    let further = (end + 1..chars.len()).find(|j| !is_name(chars[*j])).unwrap_or(chars.len());
+                    let name: String = chars[i..further].iter().collect();
+                    if further == end + 1 || !headers.iter().any(|h| h == &name || h.starts_with(&format!("{}/", name))) {
+                        break;
+                    }
+                    end = further;
+                }
+                (Token::Name(chars[i..end].iter().collect()), end - i)
+            }
+            ('=', Some('=')) => (Token::Op(Op::Eq), 2),
+            ('!', Some('=')) | ('<', Some('>')) => (Token::Op(Op::Ne), 2),
+            ('<', Some('=')) => (Token::Op(Op::Le), 2),
+            ('>', Some('=')) => (Token::Op(Op::Ge), 2),
+            ('&', Some('&')) => (Token::Op(Op::And), 2),
+            ('|', Some('|')) => (Token::Op(Op::Or), 2),
+            ('=', _) => (Token::Op(Op::Eq), 1),
+            ('<', _) => (Token::Op(Op::Lt), 1),
+            ('>', _) => (Token::Op(Op::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('+', _) => (Token::Op(Op::Add), 1),
+            ('-', _) => (Token::Op(Op::Sub), 1),
+            ('*', _) => (Token::Op(Op::Mul), 1),
+            ('/', _) => (Token::Op(Op::Div), 1),
+            ('%', _) => (Token::Op(Op::Rem), 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            (',', _) => (Token::Comma, 1),
+            (c, _) => return Err(format!("unexpected {}", c)),
+        };
+        let token = match token {
+            Token::Name(name) if name.eq_ignore_ascii_case("and") => Token::Op(Op::And),
+            Token::Name(name) if name.eq_ignore_ascii_case("or") => Token::Op(Op::Or),
+            token => token,
+        };
+        tokens.push(token);
+        i += length;
+    }
+    Ok(tokens)
+}
+
+/// Precedence climbing over the tokens.
+struct Parser<'h> {
+    tokens: Vec<Token>,
+    position: usize,
+    headers: &'h [String],
+}
+
+impl<'h> Parser<'h> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(op)) = self.peek().cloned() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.position += 1;
+            let right = self.expression(op.precedence() + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Op(Op::Sub)) => {
+                self.position += 1;
+                Ok(Expr::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(Value::Number(n))),
+            Some(Token::Text(t)) => Ok(Expr::Literal(Value::Text(t))),
+            Some(Token::Open) => {
+                let expr = self.expression(0)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("missing )".to_string()),
+                }
+            }
+            Some(Token::Name(name)) if self.peek() == Some(&Token::Open) => {
+                self.position += 1;
+                let function = name.to_lowercase();
+                if !FUNCTIONS.contains(&function.as_str()) {
+                    return Err(format!("unknown function {}", name));
+                }
+                let mut args = vec![];
+                if self.peek() == Some(&Token::Close) {
+                    self.position += 1;
+                    return Ok(Expr::Call(function, args));
+                }
+                loop {
+                    args.push(self.expression(0)?);
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::Close) => return Ok(Expr::Call(function, args)),
+                        _ => return Err(format!("missing ) after the arguments of {}", name)),
+                    }
+                }
+            }
+            Some(Token::Name(name)) => match self.headers.iter().position(|h| *h == name) {
+                Some(c) => Ok(Expr::Column(c)),
+                None => match name.to_lowercase().as_str() {
+                    "true" => Ok(Expr::Literal(Value::Bool(true))),
+                    "false" => Ok(Expr::Literal(Value::Bool(false))),
+                    "null" => Ok(Expr::Literal(Value::Null)),
+                    _ => Err(format!("unknown column {}", name)),
+                },
+            },
+            Some(token) => Err(format!("unexpected {}", token.describe())),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers() -> Vec<String> {
+        ["amount", "name/first", "name/last", "unit price", "qty"].iter().map(|h| h.to_string()).collect()
+    }
+
+    fn eval(input: &str, row: &[&str]) -> Result<Value, String> {
+        Expr::parse(input, &headers())?.eval(&StringRecord::from(row.to_vec()))
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(eval("1 + 2 * 3", &[]), Ok(Value::Number(7.0)));
+        assert_eq!(eval("(1 + 2) * 3", &[]), Ok(Value::Number(9.0)));
+        assert_eq!(eval("10 - 4 - 3", &[]), Ok(Value::Number(3.0)));
+        assert_eq!(eval("12 / 3 / 2", &[]), Ok(Value::Number(2.0)));
+    }
+
+    #[test]
+    fn comparisons_bind_tighter_than_and_or() {
+        assert_eq!(eval("1 + 1 == 2 and 3 > 2", &[]), Ok(Value::Bool(true)));
+        assert_eq!(eval("1 > 2 or 2 > 1 and 0 > 1", &[]), Ok(Value::Bool(false)));
+        assert_eq!(eval("1 > 2 || 3 > 2 && 2 > 1", &[]), Ok(Value::Bool(true)));
+    }
+
+    #[test]
+    fn unary_minus_binds_tightest() {
+        assert_eq!(eval("-2 * 3", &[]), Ok(Value::Number(-6.0)));
+        assert_eq!(eval("2 - -3", &[]), Ok(Value::Number(5.0)));
+        assert_eq!(eval("-(1 + 2)", &[]), Ok(Value::Number(-3.0)));
+        assert_eq!(eval("-amount", &["4"]), Ok(Value::Number(-4.0)));
+        assert_eq!(eval("-amount", &[""]), Ok(Value::Null));
+    }
+
+    #[test]
+    fn slashes_name_nested_columns_or_divide() {
+        let row = ["10", "Ann", "Lee", "2.5", "4"];
+        assert_eq!(Expr::parse("name/first", &headers()), Ok(Expr::Column(1)));
+        assert_eq!(eval("concat(name/first, \" \", name/last)", &row), Ok(Value::Text("Ann Lee".to_string())));
+        // amount/qty is not a column, so it divides
+        assert_eq!(eval("amount/qty", &row), Ok(Value::Number(2.5)));
+    }
+
+    #[test]
+    fn backticks_quote_names_with_spaces() {
+        let row = ["10", "Ann", "Lee", "2.5", "4"];
+        assert_eq!(Expr::parse("`unit price`", &headers()), Ok(Expr::Column(3)));
+        assert_eq!(eval("`unit price` * qty", &row), Ok(Value::Number(10.0)));
+        assert_eq!(eval("`name/last`", &row), Ok(Value::Text("Lee".to_string())));
+    }
+
+    #[test]
+    fn parse_errors_name_the_problem() {
+        let error = |input: &str| Expr::parse(input, &headers()).unwrap_err();
+        assert_eq!(error("price * 2"), "unknown column price");
+        assert_eq!(error("frobnicate(amount)"), "unknown function frobnicate");
+        assert_eq!(error("(amount + 1"), "missing )");
+        assert_eq!(error("round(amount, 2"), "missing ) after the arguments of round");
+        assert_eq!(error("amount +"), "unexpected end of expression");
+        assert_eq!(error("\"open"), "unterminated quote");
+        assert_eq!(error("amount # 2"), "unexpected #");
+        assert_eq!(error("amount 2"), "unexpected 2");
+        assert_eq!(error("amount * )"), "unexpected )");
+    }
+
+    #[test]
+    fn evaluation_errors_name_the_problem() {
+        assert_eq!(eval("amount / 0", &["1"]), Err("division by zero".to_string()));
+        assert_eq!(eval("name/first * 2", &["1", "Ann"]), Err("Ann is not a number".to_string()));
+        assert_eq!(eval("upper(amount, qty)", &["1"]), Err("upper takes 1 argument(s)".to_string()));
+        assert_eq!(eval("year(name/first)", &["1", "Ann"]), Err("Ann is not a date".to_string()));
+    }
+
+    #[test]
+    fn numbers_are_stored_in_full() {
+        assert_eq!(eval("amount / 3", &["1"]).map(|v| v.to_cell()), Ok("0.3333333333333333".to_string()));
+        assert_eq!(eval("amount * 1000", &["0.0000123"]).map(|v| v.to_cell()), Ok("0.0123".to_string()));
+        assert_eq!(Value::Number(0.00001234).to_cell(), "0.00001234");
+        assert_eq!(Value::Number(123456789012.5).to_cell(), "123456789012.5");
+        assert_eq!(Value::Number(1e20).to_cell(), "100000000000000000000");
+        assert_eq!(Value::Number(-2.75).to_cell(), "-2.75");
+        assert_eq!(Value::Number(-3.0).to_cell(), "-3");
+        assert_eq!(Value::Number(-0.0).to_cell(), "0");
+        assert_eq!(Value::Number(42.0).to_cell(), "42");
+    }
+
+    #[test]
+    fn null_is_only_equal_to_null() {
+        assert_eq!(eval("amount == null", &[""]), Ok(Value::Bool(true)));
+        assert_eq!(eval("amount != null", &[""]), Ok(Value::Bool(false)));
+        assert_eq!(eval("amount == null", &["0"]), Ok(Value::Bool(false)));
+        assert_eq!(eval("amount == 0", &[""]), Ok(Value::Bool(false)));
+        assert_eq!(eval("amount != 0", &[""]), Ok(Value::Bool(true)));
+        assert_eq!(eval("name/first == \"\"", &["1", ""]), Ok(Value::Bool(false)));
+    }
+
+    #[test]
+    fn ordering_with_null_is_null() {
+        assert_eq!(Value::Null.compare(&Value::Number(1.0)), None);
+        assert_eq!(Value::Null.compare(&Value::Null), None);
+        assert_eq!(eval("amount < 10", &[""]), Ok(Value::Null));
+        assert_eq!(eval("amount >= 10", &[""]), Ok(Value::Null));
+        assert_eq!(eval("name/first > \"\"", &["1", ""]), Ok(Value::Null));
+        assert_eq!(eval("amount < 10", &["-5"]), Ok(Value::Bool(true)));
+    }
+}
@@ -0,0 +1,131 @@
+use csv::{ReaderBuilder, StringRecord};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+
+/// Reads the records appended to a file since the last look, like `tail -f`.
+pub struct Follow {
+    path: String,
+    offset: u64,
+    // the end of a record that is still being written
+    partial: Vec<u8>,
+}
+
+impl Follow {
+    /// Follows `path` from its current end.
+    pub fn new(path: &str) -> io::Result<Follow> {
+        let offset = std::fs::metadata(path)?.len();
+        Ok(Follow { path: path.to_string(), offset, partial: vec![] })
+    }
+
+    /// Complete records written since the last call. A file that shrank was
+    /// truncated or replaced and is followed from its new end.
+    pub fn poll(&mut self) -> io::Result<Vec<StringRecord>> {
+        let len = std::fs::metadata(&self.path)?.len();
+        if len < self.offset {
+            self.offset = len;
+            self.partial.clear();
+        }
+        if len == self.offset {
+            return Ok(vec![]);
+        }
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let read = file.take(len - self.offset).read_to_end(&mut self.partial)?;
+        self.offset += read as u64;
+
+        let Some(end) = record_end(&self.partial) else { return Ok(vec![]) };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        let records = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(complete.as_slice())
+            .records()
+            .filter_map(|r| r.ok())
+            .filter(|r| !(r.len() == 1 && r[0].is_empty()))
+            .collect();
+        Ok(records)
+    }
+}
+
+/// Where the last complete record of `data` ends: the last line break that
+/// is not inside a quoted field, which may span lines.
+fn record_end(data: &[u8]) -> Option<usize> {
+    let mut quoted = false;
+    let mut end = None;
+    for (i, b) in data.iter().enumerate() {
+        match b {
+            // an escaped quote (`""`) flips twice and changes nothing
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => end = Some(i),
+            _ => {}
+        }
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("csv-grep-follow-{}-{}.csv", std::process::id(), name))
+    }
+
+    fn append(path: &PathBuf, text: &str) {
+        OpenOptions::new().append(true).create(true).open(path).unwrap().write_all(text.as_bytes()).unwrap();
+    }
+
+    fn rows(records: Vec<StringRecord>) -> Vec<Vec<String>> {
+        records.iter().map(|r| r.iter().map(|f| f.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn partial_lines_wait_for_their_end() {
+        let path = path("partial");
+        std::fs::write(&path, "a,b\n").unwrap();
+        let mut follow = Follow::new(path.to_str().unwrap()).unwrap();
+        assert!(follow.poll().unwrap().is_empty());
+        append(&path, "1,2\n3,");
+        assert_eq!(rows(follow.poll().unwrap()), vec![vec!["1", "2"]]);
+        assert!(follow.poll().unwrap().is_empty());
+        append(&path, "4\n");
+        assert_eq!(rows(follow.poll().unwrap()), vec![vec!["3", "4"]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn quoted_line_breaks_do_not_end_a_record() {
+        let path = path("quoted");
+        std::fs::write(&path, "a,b\n").unwrap();
+        let mut follow = Follow::new(path.to_str().unwrap()).unwrap();
+        append(&path, "5,\"two\nli");
+        assert!(follow.poll().unwrap().is_empty());
+        append(&path, "nes, \"\"quoted\"\"\"\n6,x\n");
+        assert_eq!(rows(follow.poll().unwrap()), vec![vec!["5", "two\nlines, \"quoted\""], vec!["6", "x"]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_truncated_file_is_followed_from_its_new_end() {
+        let path = path("truncated");
+        std::fs::write(&path, "a,b\n1,2\n").unwrap();
+        let mut follow = Follow::new(path.to_str().unwrap()).unwrap();
+        std::fs::write(&path, "a,b\n").unwrap();
+        assert!(follow.poll().unwrap().is_empty());
+        append(&path, "7,8\n");
+        assert_eq!(rows(follow.poll().unwrap()), vec![vec!["7", "8"]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn records_end_at_the_last_unquoted_line_break() {
+        assert_eq!(record_end(b"1,2\n3,4"), Some(3));
+        assert_eq!(record_end(b"1,\"a\nb"), None);
+        assert_eq!(record_end(b"1,\"a\"\"\nb\"\n2"), Some(9));
+        assert_eq!(record_end(b""), None);
+    }
+}
@@ -0,0 +1,189 @@
+use csv::StringRecord;
+use ratatui::widgets::TableState;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use crate::tui::stats;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Display, EnumIter, EnumString)]
+pub enum Aggregate {
+    #[strum(to_string = "count")]
+    Count,
+    #[strum(to_string = "sum")]
+    Sum,
+    #[strum(to_string = "mean")]
+    Mean,
+    #[strum(to_string = "min")]
+    Min,
+    #[strum(to_string = "max")]
+    Max,
+    #[strum(to_string = "median")]
+    Median,
+    #[strum(to_string = "distinct")]
+    DistinctCount,
+}
+
+impl Aggregate {
+    pub fn compute(&self, data: &stats::Data) -> Option<f64> {
+        match self {
+            Aggregate::Count => Some(data.data.len() as f64),
+            Aggregate::Sum => Some(data.sum()),
+            Aggregate::Mean if data.data.is_empty() => None,
+            Aggregate::Mean => Some(data.mean()),
+            Aggregate::Min => data.min(),
+            Aggregate::Max => data.max(),
+            Aggregate::Median => data.median(),
+            Aggregate::DistinctCount => Some(data.distinct_count() as f64),
+        }
+    }
+}
+
+/// Rows grouped by one or more key columns with aggregates over the numeric
+/// columns, as shown in the Group By tab.
+pub struct GroupBy {
+    pub keys: Vec<usize>,
+    pub aggregates: Vec<Aggregate>,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    // (column, descending)
+    pub sort: Option<(usize, bool)>,
+    pub column: usize,
+    pub state: TableState,
+    pub generation: Option<usize>,
+}
+
+impl GroupBy {
+    pub fn new() -> GroupBy {
+        GroupBy {
+            keys: vec![],
+            aggregates: vec![Aggregate::Count, Aggregate::Mean],
+            headers: vec![],
+            rows: vec![],
+            sort: None,
+            column: 0,
+            state: TableState::default().with_selected(0),
+            generation: None,
+        }
+    }
+
+    pub fn toggle_key(&mut self, column: usize) {
+        match self.keys.iter().position(|k| *k == column) {
+            Some(i) => {
+                self.keys.remove(i);
+            }
+            None => self.keys.push(column),
+        }
+        self.generation = None;
+    }
+
+    /// Toggles the n-th aggregate of `Aggregate::iter()`, keeping at least one.
+    pub fn toggle_aggregate(&mut self, n: usize) {
+        let Some(aggregate) = Aggregate::iter().nth(n) else { return };
+        match self.aggregates.iter().position(|a| *a == aggregate) {
+            Some(i) if self.aggregates.len() > 1 => {
+                self.aggregates.remove(i);
+            }
+            Some(_) => {}
+            None => {
+                self.aggregates.push(aggregate);
+                self.aggregates.sort_by_key(|a| Aggregate::iter().position(|b| b == *a));
+            }
+        }
+        self.generation = None;
+    }
+
+    /// Groups `records` by the key columns and aggregates every numeric column.
+    pub fn compute(&mut self, headers: &[String], records: &[StringRecord], numeric: &[usize]) {
+        let values: Vec<usize> = numeric.iter().copied().filter(|c| !self.keys.contains(c)).collect();
+        let mut groups: BTreeMap<Vec<String>, Vec<&StringRecord>> = BTreeMap::new();
+        for record in records {
+            let key = self.keys.iter().map(|k| record.get(*k).unwrap_or("").to_string()).collect();
+            groups.entry(key).or_default().push(record);
+        }
+
+        self.headers = self.keys.iter().map(|k| headers[*k].clone()).collect();
+        self.headers.push("rows".to_string());
+        for c in &values {
+            for aggregate in &self.aggregates {
+                self.headers.push(format!("{}({})", aggregate, headers[*c]));
+            }
+        }
+
+        self.rows = groups
+            .into_iter()
+            .map(|(key, members)| {
+                let mut row = key;
+                row.push(members.len().to_string());
+                for c in &values {
+                    let data = stats::Data {
+                        data: members.iter().filter_map(|r| r.get(*c)?.trim().parse::<f64>().ok()).collect(),
+                    };
+                    for aggregate in &self.aggregates {
+                        row.push(aggregate.compute(&data).map(format_value).unwrap_or_default());
+                    }
+                }
+                row
+            })
+            .collect();
+        self.column = self.column.min(self.headers.len() - 1);
+        self.apply_sort();
+    }
+
+    /// Sorts by the column under the cursor, flipping the order when it is
+    /// already the sort column.
+    pub fn sort_by_column(&mut self) {
+        self.sort = match self.sort {
+            Some((c, descending)) if c == self.column => Some((c, !descending)),
+            _ => Some((self.column, false)),
+        };
+        self.apply_sort();
+    }
+
+    fn apply_sort(&mut self) {
+        let Some((column, descending)) = self.sort else { return };
+        self.rows.sort_by(|a, b| {
+            let ordering = compare_cells(&a[column], &b[column]);
+            if descending { ordering.reverse() } else { ordering }
+        });
+    }
+
+    /// Labels and values of the aggregate under the cursor (or the first
+    /// aggregate column) for the bar chart.
+    pub fn chart_series(&self) -> Option<(String, Vec<(String, f64)>)> {
+        let first_value = self.keys.len();
+        let column = if self.column >= first_value { self.column } else { first_value };
+        let name = self.headers.get(column)?.clone();
+        let bars = self
+            .rows
+            .iter()
+            .map(|r| (r[..self.keys.len()].join(" / "), r[column].parse::<f64>().unwrap_or(0.0)))
+            .collect();
+        Some((name, bars))
+    }
+
+    pub fn next(&mut self) {
+        let i = self.state.selected().map(|i| i + 1).unwrap_or(0);
+        self.state.select(Some(i.min(self.rows.len().saturating_sub(1))));
+    }
+
+    pub fn previous(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.state.select(Some(i.saturating_sub(1)));
+    }
+}
+
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.total_cmp(&y),
+        _ => a.cmp(b),
+    }
+}
+
+pub fn format_value(v: f64) -> String {
+    if v.fract() == 0.0 && v.abs() < 1e15 {
+        format!("{}", v as i64)
+    } else {
+        format!("{:.4}", v).trim_end_matches('0').to_string()
+    }
+}
@@ -0,0 +1,219 @@
+use csv::StringRecord;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use crate::tui::loaders::Dataset;
+use crate::tui::sql;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Display, EnumString)]
+pub enum JoinKind {
+    #[strum(to_string = "inner")]
+    Inner,
+    #[strum(to_string = "left")]
+    Left,
+    #[strum(to_string = "right")]
+    Right,
+    #[strum(to_string = "full")]
+    Full,
+}
+
+/// A file to join to the loaded one, e.g. `customers.csv on customer=id left`.
+#[derive(Clone, Debug)]
+pub struct JoinSpec {
+    pub path: String,
+    // (column of the loaded file, column of the other file)
+    pub keys: Vec<(String, String)>,
+    pub kind: JoinKind,
+}
+
+impl JoinSpec {
+    /// Parses `<path> on <key>[=<other key>][, ...] [inner|left|right|full]`,
+    /// an inner join by default.
+    pub fn parse(input: &str) -> Result<JoinSpec, String> {
+        let usage = "usage: <path> on <key>[=<other key>][, ...] [inner|left|right|full]";
+        let (path, rest) = input.split_once(" on ").ok_or(usage)?;
+        let mut rest = rest.trim();
+        let mut kind = JoinKind::Inner;
+        if let Some((keys, last)) = rest.rsplit_once(' ') {
+            if let Ok(k) = JoinKind::from_str(last) {
+                kind = k;
+                rest = keys;
+            }
+        }
+        let keys: Vec<(String, String)> = rest
+            .split(',')
+            .map(|k| match k.split_once('=') {
+                Some((left, right)) => (left.trim().to_string(), right.trim().to_string()),
+                None => (k.trim().to_string(), k.trim().to_string()),
+            })
+            .filter(|(left, right)| !left.is_empty() && !right.is_empty())
+            .collect();
+        if keys.is_empty() {
+            return Err(usage.to_string());
+        }
+        Ok(JoinSpec { path: path.trim().to_string(), keys, kind })
+    }
+}
+
+/// The joined table and how many distinct keys of either side found no match.
+pub struct Joined {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    pub unmatched_left: usize,
+    pub unmatched_right: usize,
+}
+
+/// Joins `right` to the loaded table. The key columns appear once, holding
+/// the key of whichever side has the row; other columns of `right` whose
+/// names are taken are prefixed with its file name, e.g. `customers/name`.
+/// As in SQL, a row with an empty key cell matches nothing, not even other
+/// rows with empty keys.
+pub fn join(headers: &[String], records: &[StringRecord], right: &Dataset, spec: &JoinSpec) -> Result<Joined, String> {
+    let position = |headers: &[String], name: &str, which: &str| {
+        headers.iter().position(|h| h == name).ok_or(format!("no column {} in the {} file", name, which))
+    };
+    let mut left_keys = vec![];
+    let mut right_keys = vec![];
+    for (l, r) in &spec.keys {
+        left_keys.push(position(headers, l, "loaded")?);
+        right_keys.push(position(&right.headers, r, "other")?);
+    }
+    let key = |record: &StringRecord, columns: &[usize]| -> Option<Vec<String>> {
+        columns
+            .iter()
+            .map(|c| record.get(*c).filter(|v| !v.is_empty()).map(|v| v.to_string()))
+            .collect()
+    };
+
+    let prefix = sql::table_name(&spec.path);
+    let right_columns: Vec<usize> = (0..right.headers.len()).filter(|c| !right_keys.contains(c)).collect();
+    let mut joined_headers = headers.to_vec();
+    for c in &right_columns {
+        let name = &right.headers[*c];
+        joined_headers.push(match headers.contains(name) {
+            true => format!("{}/{}", prefix, name),
+            false => name.clone(),
+        });
+    }
+
+    let mut index: HashMap<Vec<String>, Vec<usize>> = HashMap::new();
+    for (i, record) in right.records.iter().enumerate() {
+        if let Some(k) = key(record, &right_keys) {
+            index.entry(k).or_default().push(i);
+        }
+    }
+    let field = |record: &StringRecord, c: usize| record.get(c).unwrap_or("").to_string();
+    let keep_left = matches!(spec.kind, JoinKind::Left | JoinKind::Full);
+    let keep_right = matches!(spec.kind, JoinKind::Right | JoinKind::Full);
+
+    let mut joined = vec![];
+    let mut matched_right = vec![false; right.records.len()];
+    let mut unmatched_left: HashSet<Vec<String>> = HashSet::new();
+    for record in records {
+        let k = key(record, &left_keys);
+        let left_cells = (0..headers.len()).map(|c| field(record, c));
+        match k.as_ref().and_then(|k| index.get(k)) {
+            Some(rows) => {
+                for r in rows {
+                    matched_right[*r] = true;
+                    let right_cells = right_columns.iter().map(|c| field(&right.records[*r], *c));
+                    joined.push(left_cells.clone().chain(right_cells).collect::<StringRecord>());
+                }
+            }
+            None => {
+                if keep_left {
+                    let empty = right_columns.iter().map(|_| String::new());
+                    joined.push(left_cells.chain(empty).collect::<StringRecord>());
+                }
+                unmatched_left.extend(k);
+            }
+        }
+    }
+    let mut unmatched_right: HashSet<Vec<String>> = HashSet::new();
+    for (record, _) in right.records.iter().zip(&matched_right).filter(|(_, m)| !**m) {
+        unmatched_right.extend(key(record, &right_keys));
+        if keep_right {
+            let left_cells = (0..headers.len()).map(|c| match left_keys.iter().position(|k| *k == c) {
+                Some(i) => field(record, right_keys[i]),
+                None => String::new(),
+            });
+            let right_cells = right_columns.iter().map(|c| field(record, *c));
+            joined.push(left_cells.chain(right_cells).collect::<StringRecord>());
+        }
+    }
+    Ok(Joined {
+        headers: joined_headers,
+        records: joined,
+        unmatched_left: unmatched_left.len(),
+        unmatched_right: unmatched_right.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn records(rows: &[&[&str]]) -> Vec<StringRecord> {
+        rows.iter().map(|r| StringRecord::from(r.to_vec())).collect()
+    }
+
+    fn rows(joined: &Joined) -> Vec<Vec<&str>> {
+        joined.records.iter().map(|r| r.iter().collect()).collect()
+    }
+
+    fn customers() -> Dataset {
+        let rows = records(&[&["1", "Ann", "Berlin"], &["2", "Bob", "Paris"], &["", "Nobody", "Rome"]]);
+        Dataset::new("customers.csv", "CSV", strings(&["id", "name", "city"]), rows)
+    }
+
+    fn join_orders(input: &str) -> Joined {
+        let orders = records(&[&["a", "1", "pen"], &["b", "3", "ink"], &["c", "", "pad"], &["d", "1", "cap"]]);
+        let spec = JoinSpec::parse(input).unwrap();
+        join(&strings(&["order", "customer", "name"]), &orders, &customers(), &spec).unwrap()
+    }
+
+    #[test]
+    fn spec_reads_keys_and_kind() {
+        let spec = JoinSpec::parse("customers.csv on customer=id, day left").unwrap();
+        assert_eq!(spec.path, "customers.csv");
+        assert_eq!(spec.keys, vec![("customer".to_string(), "id".to_string()), ("day".to_string(), "day".to_string())]);
+        assert_eq!(spec.kind, JoinKind::Left);
+        assert_eq!(JoinSpec::parse("customers.csv on id").unwrap().kind, JoinKind::Inner);
+        assert!(JoinSpec::parse("customers.csv").is_err());
+    }
+
+    #[test]
+    fn inner_join_matches_every_row_with_the_key() {
+        let joined = join_orders("customers.csv on customer=id");
+        assert_eq!(rows(&joined), vec![vec!["a", "1", "pen", "Ann", "Berlin"], vec!["d", "1", "cap", "Ann", "Berlin"]]);
+        assert_eq!((joined.unmatched_left, joined.unmatched_right), (1, 1));
+    }
+
+    #[test]
+    fn taken_column_names_of_the_other_file_are_prefixed() {
+        let joined = join_orders("customers.csv on customer=id");
+        assert_eq!(joined.headers, strings(&["order", "customer", "name", "customers/name", "city"]));
+    }
+
+    #[test]
+    fn empty_keys_do_not_match_each_other() {
+        let joined = join_orders("customers.csv on customer=id full");
+        assert!(!rows(&joined).contains(&vec!["c", "", "pad", "Nobody", "Rome"]));
+        assert!(rows(&joined).contains(&vec!["c", "", "pad", "", ""]));
+        assert!(rows(&joined).contains(&vec!["", "", "", "Nobody", "Rome"]));
+        // empty keys are not counted as unmatched keys either
+        assert_eq!((joined.unmatched_left, joined.unmatched_right), (1, 1));
+    }
+
+    #[test]
+    fn right_join_fills_the_key_from_the_other_file() {
+        let joined = join_orders("customers.csv on customer=id right");
+        assert!(rows(&joined).contains(&vec!["", "2", "", "Bob", "Paris"]));
+        assert_eq!(joined.records.len(), 4);
+    }
+}
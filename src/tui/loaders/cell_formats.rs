@@ -0,0 +1,320 @@
+use quick_xml::escape::unescape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use zip::ZipArchive;
+
+// symbols that make a number format a currency format
+const CURRENCY_SYMBOLS: [char; 11] = ['$', '€', '£', '¥', '₹', '₽', '₩', '₪', '₫', '₺', '¢'];
+// repeated rows and columns of an ods sheet looked at, the rest are padding
+const MAX_REPEAT: usize = 1000;
+const MAX_PERCENT_DECIMALS: usize = 4;
+
+
+/// How a number cell is shown in the workbook, besides dates which calamine
+/// already converts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NumberFormat {
+    // digits after the decimal point
+    Percent(usize),
+    Currency { symbol: String, decimals: usize, after: bool },
+}
+
+impl NumberFormat {
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            NumberFormat::Percent(decimals) => format!("{:.*}%", decimals, value * 100.0),
+            NumberFormat::Currency { symbol, decimals, after: true } => format!("{:.*} {}", decimals, value, symbol),
+            NumberFormat::Currency { symbol, decimals, after: false } => {
+                let sign = if value < 0.0 { "-" } else { "" };
+                format!("{}{}{:.*}", sign, symbol, decimals, value.abs())
+            }
+        }
+    }
+}
+
+/// The percent and currency formats of the cells of a sheet by (row, column),
+/// counted from the top left corner of the sheet. Only xlsx and ods files
+/// expose them; for other workbooks, or when the file cannot be read this
+/// way, the map is empty and numbers are shown as they are.
+pub fn read(file_path: &str, sheet: &str) -> HashMap<(u32, u32), NumberFormat> {
+    let extension = std::path::Path::new(file_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let formats = match extension.as_str() {
+        "xlsx" | "xlsm" => xlsx(file_path, sheet),
+        "ods" => ods(file_path, sheet),
+        _ => None,
+    };
+    formats.unwrap_or_default()
+}
+
+fn xlsx(file_path: &str, sheet: &str) -> Option<HashMap<(u32, u32), NumberFormat>> {
+    let mut zip = ZipArchive::new(File::open(file_path).ok()?).ok()?;
+    let mut id = None;
+    for_each_element(&entry(&mut zip, "xl/workbook.xml")?, |e, _| {
+        if e.local_name().as_ref() == b"sheet" && attribute(e, b"name").as_deref() == Some(sheet) {
+            id = attribute(e, b"id");
+        }
+    });
+    let id = id?;
+    let mut target = None;
+    for_each_element(&entry(&mut zip, "xl/_rels/workbook.xml.rels")?, |e, _| {
+        if e.local_name().as_ref() == b"Relationship" && attribute(e, b"Id").as_deref() == Some(id.as_str()) {
+            target = attribute(e, b"Target");
+        }
+    });
+    let target = target?;
+    let path = match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("xl/{}", target),
+    };
+
+    // custom format codes by id, then the format of every cell style in order
+    let mut codes: HashMap<String, String> = HashMap::new();
+    let mut styles: Vec<Option<NumberFormat>> = vec![];
+    let mut in_cell_xfs = false;
+    for_each_element(&entry(&mut zip, "xl/styles.xml")?, |e, start| match e.local_name().as_ref() {
+        b"cellXfs" => in_cell_xfs = start,
+        b"numFmt" => {
+            if let (Some(id), Some(code)) = (attribute(e, b"numFmtId"), attribute(e, b"formatCode")) {
+                codes.insert(id, code);
+            }
+        }
+        b"xf" if in_cell_xfs => {
+            let id = attribute(e, b"numFmtId").unwrap_or_default();
+            styles.push(match codes.get(&id) {
+                Some(code) => parse_code(code),
+                None => builtin(&id),
+            });
+        }
+        _ => {}
+    });
+
+    let mut formats = HashMap::new();
+    for_each_element(&entry(&mut zip, &path)?, |e, _| {
+        if e.local_name().as_ref() != b"c" {
+            return;
+        }
+        let style = attribute(e, b"s").and_then(|s| s.parse::<usize>().ok());
+        let format = style.and_then(|s| styles.get(s).cloned().flatten());
+        if let (Some(format), Some(position)) = (format, attribute(e, b"r").and_then(|r| cell_position(&r))) {
+            formats.insert(position, format);
+        }
+    });
+    Some(formats)
+}
+
+fn ods(file_path: &str, sheet: &str) -> Option<HashMap<(u32, u32), NumberFormat>> {
+    let mut zip = ZipArchive::new(File::open(file_path).ok()?).ok()?;
+    let content = entry(&mut zip, "content.xml")?;
+    let mut formats = HashMap::new();
+    let mut in_sheet = false;
+    let (mut row, mut column, mut row_repeat) = (0usize, 0usize, 1usize);
+    let mut row_formats: Vec<(usize, NumberFormat)> = vec![];
+    for_each_element(&content, |e, start| match e.local_name().as_ref() {
+        b"table" => {
+            in_sheet = start && attribute(e, b"name").as_deref() == Some(sheet);
+            row = 0;
+        }
+        b"table-row" if in_sheet && start => {
+            column = 0;
+            row_repeat = repeat(e, b"number-rows-repeated");
+            row_formats.clear();
+        }
+        b"table-row" if in_sheet => {
+            for r in row..row + row_repeat {
+                for (c, format) in &row_formats {
+                    formats.insert((r as u32, *c as u32), format.clone());
+                }
+            }
+            row += row_repeat;
+        }
+        b"table-cell" | b"covered-table-cell" if in_sheet && start => {
+            let columns = repeat(e, b"number-columns-repeated");
+            let format = match attribute(e, b"value-type").as_deref() {
+                // ods keeps the value as typed, so show the digits it has
+                Some("percentage") => Some(NumberFormat::Percent(decimals(&attribute(e, b"value").unwrap_or_default()))),
+                Some("currency") => Some(NumberFormat::Currency {
+                    symbol: currency_symbol(&attribute(e, b"currency").unwrap_or_default()),
+                    decimals: 2,
+                    after: false,
+                }),
+                _ => None,
+            };
+            if let Some(format) = format {
+                row_formats.extend((column..column + columns).map(|c| (c, format.clone())));
+            }
+            column += columns;
+        }
+        _ => {}
+    });
+    Some(formats)
+}
+
+/// Calls `f` with every element of `xml` and whether it opens (true) or
+/// closes (false) it. Self closing elements only open.
+fn for_each_element(xml: &str, mut f: impl FnMut(&BytesStart, bool)) {
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => f(&e, true),
+            Ok(Event::End(e)) => f(&BytesStart::new(String::from_utf8_lossy(e.name().as_ref()).to_string()), false),
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+}
+
+fn entry(zip: &mut ZipArchive<File>, name: &str) -> Option<String> {
+    let mut text = String::new();
+    zip.by_name(name).ok()?.read_to_string(&mut text).ok()?;
+    Some(text)
+}
+
+/// An attribute by its name without the namespace prefix, e.g. `id` for `r:id`.
+fn attribute(e: &BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .find(|a| a.key.local_name().as_ref() == name)
+        .map(|a| {
+            let raw = String::from_utf8_lossy(&a.value).to_string();
+            unescape(&raw).map(|v| v.to_string()).unwrap_or(raw)
+        })
+}
+
+fn repeat(e: &BytesStart, name: &[u8]) -> usize {
+    attribute(e, name).and_then(|n| n.parse().ok()).unwrap_or(1).clamp(1, MAX_REPEAT)
+}
+
+/// `B12` as (11, 1).
+fn cell_position(reference: &str) -> Option<(u32, u32)> {
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    let column = letters.chars().try_fold(0u32, |n, c| c.is_ascii_uppercase().then(|| n * 26 + (c as u32 - 'A' as u32 + 1)))?;
+    let row: u32 = digits.parse().ok()?;
+    Some((row.checked_sub(1)?, column.checked_sub(1)?))
+}
+
+/// The built in formats of xlsx that are percentages or dollar amounts.
+fn builtin(id: &str) -> Option<NumberFormat> {
+    let dollars = |decimals| Some(NumberFormat::Currency { symbol: "$".to_string(), decimals, after: false });
+    match id {
+        "9" => Some(NumberFormat::Percent(0)),
+        "10" => Some(NumberFormat::Percent(2)),
+        "5" | "6" | "42" => dollars(0),
+        "7" | "8" | "44" => dollars(2),
+        _ => None,
+    }
+}
+
+/// Reads a format code such as `0.0%`, `"$"#,##0.00` or `#,##0.00 [$€-407]`,
+/// looking only at the section for positive numbers.
+fn parse_code(code: &str) -> Option<NumberFormat> {
+    let mut symbol: Option<String> = None;
+    let mut after = false;
+    let mut digits_seen = false;
+    let mut placeholders = String::new();
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        let found = match c {
+            ';' => break,
+            '"' => Some(chars.by_ref().take_while(|c| *c != '"').collect::<String>()),
+            '[' => {
+                let bracket: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                bracket.strip_prefix('$').map(|s| s.split('-').next().unwrap_or("").to_string())
+            }
+            '\\' => chars.next().map(|c| c.to_string()),
+            c if CURRENCY_SYMBOLS.contains(&c) => Some(c.to_string()),
+            c => {
+                digits_seen |= matches!(c, '0' | '#' | '?');
+                placeholders.push(c);
+                None
+            }
+        };
+        if let Some(text) = found.map(|t| t.trim().to_string()) {
+            let is_symbol = text.chars().any(|c| CURRENCY_SYMBOLS.contains(&c)) || (c == '[' && !text.is_empty());
+            if is_symbol && symbol.is_none() {
+                symbol = Some(text);
+                after = digits_seen;
+            }
+        }
+    }
+    let decimals = placeholders
+        .split_once('.')
+        .map(|(_, fraction)| fraction.chars().take_while(|c| matches!(c, '0' | '#' | '?')).count())
+        .unwrap_or(0);
+    match symbol {
+        _ if placeholders.contains('%') => Some(NumberFormat::Percent(decimals)),
+        Some(symbol) if digits_seen => Some(NumberFormat::Currency { symbol, decimals, after }),
+        _ => None,
+    }
+}
+
+/// Digits after the decimal point of a fraction shown as a percentage, e.g.
+/// 2 for `0.1234`.
+fn decimals(value: &str) -> usize {
+    let fraction = value.split_once('.').map(|(_, f)| f.trim_end_matches('0').len()).unwrap_or(0);
+    fraction.saturating_sub(2).min(MAX_PERCENT_DECIMALS)
+}
+
+fn currency_symbol(code: &str) -> String {
+    match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" | "CNY" => "¥",
+        "INR" => "₹",
+        other => return format!("{} ", other),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(symbol: &str, decimals: usize, after: bool) -> Option<NumberFormat> {
+        Some(NumberFormat::Currency { symbol: symbol.to_string(), decimals, after })
+    }
+
+    #[test]
+    fn percent_codes_keep_their_decimals() {
+        assert_eq!(parse_code("0%"), Some(NumberFormat::Percent(0)));
+        assert_eq!(parse_code("0.00%"), Some(NumberFormat::Percent(2)));
+        assert_eq!(parse_code("#,##0.0%;[Red]-#,##0.0%"), Some(NumberFormat::Percent(1)));
+        assert_eq!(NumberFormat::Percent(1).format(0.1234), "12.3%");
+    }
+
+    #[test]
+    fn currency_symbols_go_before_or_after_the_digits() {
+        assert_eq!(parse_code("\"$\"#,##0.00"), currency("$", 2, false));
+        assert_eq!(parse_code("[$€-407] #,##0"), currency("€", 0, false));
+        assert_eq!(parse_code("#,##0.00 [$€-407]"), currency("€", 2, true));
+        assert_eq!(parse_code("#,##0.00\\ \"kr\""), None);
+        assert_eq!(parse_code("#,##0.00 [$CHF]"), currency("CHF", 2, true));
+        assert_eq!(parse_code("0.00"), None);
+        assert_eq!(parse_code("\"$\""), None);
+    }
+
+    #[test]
+    fn currencies_put_the_sign_before_a_leading_symbol() {
+        assert_eq!(currency("$", 2, false).unwrap().format(-1234.5), "-$1234.50");
+        assert_eq!(currency("€", 2, true).unwrap().format(-3.0), "-3.00 €");
+        assert_eq!(builtin("44").unwrap().format(7.126), "$7.13");
+        assert_eq!(builtin("9"), Some(NumberFormat::Percent(0)));
+        assert_eq!(builtin("14"), None);
+    }
+
+    #[test]
+    fn cell_references_count_from_zero() {
+        assert_eq!(cell_position("A1"), Some((0, 0)));
+        assert_eq!(cell_position("B12"), Some((11, 1)));
+        assert_eq!(cell_position("AA3"), Some((2, 26)));
+        assert_eq!(cell_position("A0"), None);
+        assert_eq!(cell_position("12"), None);
+    }
+}
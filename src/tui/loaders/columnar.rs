@@ -0,0 +1,220 @@
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::ipc::reader::FileReader;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use csv::StringRecord;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
+use std::fs::File;
+use super::Dataset;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Parquet,
+    ArrowIpc,
+}
+
+/// The open file and what was read of it upfront, kept for reading chunks.
+enum Reader {
+    // the metadata holds the row group offsets, parsed once
+    Parquet(File, ArrowReaderMetadata),
+    ArrowIpc(Box<FileReader<File>>),
+}
+
+/// A parquet or arrow ipc file that is read one chunk (row group or record
+/// batch) at a time, so only the part of the file that is on screen needs
+/// to be decoded.
+pub struct ColumnarSource {
+    path: String,
+    reader: Reader,
+    chunks: usize,
+    pub loaded: usize,
+}
+
+impl ColumnarSource {
+    /// Opens the file and returns an empty dataset whose column names and
+    /// numeric columns come from the embedded schema. Rows are pulled in
+    /// later through `load_next`.
+    pub fn open(path: &str, format: Format) -> Result<Dataset, String> {
+        let file = File::open(path).map_err(|e| format!("could not open {}: {}", path, e))?;
+        let (schema, chunks, mut info, reader) = match format {
+            Format::Parquet => {
+                let reader_metadata = ArrowReaderMetadata::load(&file, Default::default())
+                    .map_err(|e| format!("could not read the parquet metadata of {}: {}", path, e))?;
+                let metadata = reader_metadata.metadata();
+                let row_groups = metadata.row_groups();
+                let mut info = vec![
+                    ("Format".to_string(), "Parquet".to_string()),
+                    ("Rows".to_string(), metadata.file_metadata().num_rows().to_string()),
+                    ("Row groups".to_string(), row_groups.len().to_string()),
+                ];
+                if let Some(created_by) = metadata.file_metadata().created_by() {
+                    info.push(("Created by".to_string(), created_by.to_string()));
+                }
+                for (i, group) in row_groups.iter().enumerate() {
+                    let compression = group
+                        .columns()
+                        .iter()
+                        .map(|c| format!("{:?}", c.compression()))
+                        .collect::<std::collections::BTreeSet<String>>()
+                        .into_iter()
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    info.push((
+                        format!("Row group {}", i),
+                        format!("{} rows, {} bytes compressed, {}", group.num_rows(), group.compressed_size(), compression),
+                    ));
+                }
+                (reader_metadata.schema().clone(), row_groups.len(), info, Reader::Parquet(file, reader_metadata))
+            }
+            Format::ArrowIpc => {
+                let reader = FileReader::try_new(file, None).map_err(|e| format!("could not read {}: {}", path, e))?;
+                let info = vec![
+                    ("Format".to_string(), "Arrow IPC".to_string()),
+                    ("Record batches".to_string(), reader.num_batches().to_string()),
+                ];
+                (reader.schema(), reader.num_batches(), info, Reader::ArrowIpc(Box::new(reader)))
+            }
+        };
+
+        let headers = schema.fields().iter().map(|f| f.name().to_string()).collect();
+        let numeric = schema.fields().iter().map(|f| f.data_type().is_numeric()).collect();
+        info.push(("Path".to_string(), path.to_string()));
+        info.extend(schema_info(&schema));
+        Ok(Dataset {
+            headers,
+            records: vec![],
+            numeric: Some(numeric),
+            info,
+            source: Some(ColumnarSource { path: path.to_string(), reader, chunks, loaded: 0 }),
+            sheets: vec![],
+        })
+    }
+
+    pub fn chunks(&self) -> usize {
+        self.chunks
+    }
+
+    pub fn has_more(&self) -> bool {
+        self.loaded < self.chunks
+    }
+
+    /// Decodes the next row group or record batch into string records. A
+    /// chunk that cannot be read is an error and stays next in line.
+    pub fn load_next(&mut self) -> Result<Vec<StringRecord>, String> {
+        if !self.has_more() {
+            return Ok(vec![]);
+        }
+        let (path, loaded) = (&self.path, self.loaded);
+        let err = |e: &dyn std::fmt::Display| format!("could not read chunk {} of {}: {}", loaded + 1, path, e);
+        let batches: Vec<RecordBatch> = match &mut self.reader {
+            Reader::Parquet(file, metadata) => {
+                let file = file.try_clone().map_err(|e| err(&e))?;
+                ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata.clone())
+                    .with_row_groups(vec![loaded])
+                    .build()
+                    .map_err(|e| err(&e))?
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| err(&e))?
+            }
+            Reader::ArrowIpc(reader) => {
+                reader.set_index(loaded).map_err(|e| err(&e))?;
+                reader.next().transpose().map_err(|e| err(&e))?.into_iter().collect()
+            }
+        };
+        let mut records = vec![];
+        for batch in &batches {
+            records.extend(batch_to_records(batch).map_err(|e| err(&e))?);
+        }
+        self.loaded += 1;
+        Ok(records)
+    }
+}
+
+fn schema_info(schema: &SchemaRef) -> Vec<(String, String)> {
+    schema
+        .fields()
+        .iter()
+        .map(|f| {
+            let nullable = if f.is_nullable() { ", nullable" } else { "" };
+            (format!("Column {}", f.name()), format!("{}{}", f.data_type(), nullable))
+        })
+        .collect()
+}
+
+fn batch_to_records(batch: &RecordBatch) -> Result<Vec<StringRecord>, ArrowError> {
+    let options = FormatOptions::default();
+    let formatters: Vec<ArrayFormatter> = batch
+        .columns()
+        .iter()
+        .map(|c| ArrayFormatter::try_new(c.as_ref(), &options))
+        .collect::<Result<_, _>>()?;
+    Ok((0..batch.num_rows())
+        .map(|row| formatters.iter().map(|f| f.value(row).to_string()).collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::ipc::writer::FileWriter;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    fn batches() -> (SchemaRef, Vec<RecordBatch>) {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false), Field::new("name", DataType::Utf8, true)]));
+        let batch = |ids: Vec<i64>, names: Vec<Option<&str>>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(ids)), Arc::new(StringArray::from(names))]).unwrap()
+        };
+        let batches = vec![batch(vec![1, 2], vec![Some("a"), None]), batch(vec![3], vec![Some("c")])];
+        (schema, batches)
+    }
+
+    // every chunk as `row;row`, each row as `cell,cell`
+    fn read_all(path: &str, format: Format) -> (Dataset, Vec<String>) {
+        let mut dataset = ColumnarSource::open(path, format).unwrap();
+        let source = dataset.source.as_mut().unwrap();
+        let mut chunks = vec![];
+        while source.has_more() {
+            chunks.push(source.load_next().unwrap().iter().map(|r| r.iter().collect::<Vec<_>>().join(",")).collect::<Vec<_>>().join(";"));
+        }
+        (dataset, chunks)
+    }
+
+    #[test]
+    fn parquet_row_groups_are_read_one_by_one() {
+        let path = std::env::temp_dir().join(format!("csv-grep-columnar-{}.parquet", std::process::id()));
+        let (schema, batches) = batches();
+        let properties = WriterProperties::builder().set_max_row_group_size(2).build();
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, Some(properties)).unwrap();
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+        writer.close().unwrap();
+
+        let (dataset, chunks) = read_all(path.to_str().unwrap(), Format::Parquet);
+        assert_eq!(dataset.headers, vec!["id", "name"]);
+        assert_eq!(dataset.numeric, Some(vec![true, false]));
+        assert_eq!(chunks, vec!["1,a;2,", "3,c"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn arrow_record_batches_are_read_one_by_one() {
+        let path = std::env::temp_dir().join(format!("csv-grep-columnar-{}.arrow", std::process::id()));
+        let (schema, batches) = batches();
+        let mut writer = FileWriter::try_new(File::create(&path).unwrap(), &schema).unwrap();
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let (_, chunks) = read_all(path.to_str().unwrap(), Format::ArrowIpc);
+        assert_eq!(chunks, vec!["1,a;2,", "3,c"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
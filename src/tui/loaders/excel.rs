@@ -0,0 +1,228 @@
+use calamine::{open_workbook_auto, Data, Dimensions, Range, Reader, Sheets};
+use csv::StringRecord;
+use std::fs::File;
+use std::io::BufReader;
+use super::cell_formats::{self, NumberFormat};
+use super::Dataset;
+
+// header rows are joined the same way our csv headers are, e.g. `name/first`
+const SEPARATOR: &str = "/";
+const MAX_HEADER_ROWS: usize = 3;
+
+
+/// Reads one sheet of an xlsx/xls/xlsb/ods workbook, the first one when no
+/// sheet is given. Header rows spanned by merged cells are flattened into a
+/// single `parent/child` column name. Dates, percentages and currency amounts
+/// are shown the way the workbook formats them.
+pub fn read(file_path: &str, sheet: Option<&str>) -> Result<Dataset, String> {
+    let mut workbook = open(file_path)?;
+    let sheets = workbook.sheet_names();
+    let sheet = sheet
+        .map(|s| s.to_string())
+        .or_else(|| sheets.first().cloned())
+        .ok_or(format!("{} does not contain any sheet", file_path))?;
+    let range = workbook
+        .worksheet_range(&sheet)
+        .map_err(|e| format!("could not read sheet {}: {}", sheet, e))?;
+    let merges = merged_cells(&mut workbook, &sheet, &range);
+
+    let formats = cell_formats::read(file_path, &sheet);
+    let (top, left) = range.start().unwrap_or((0, 0));
+    let rows: Vec<Vec<String>> = range
+        .rows()
+        .enumerate()
+        .map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(c, cell)| format_cell(cell, formats.get(&(top + r as u32, left + c as u32))))
+                .collect()
+        })
+        .collect();
+    let depth = header_depth(&rows, &merges);
+    let headers = flatten_headers(&rows[..depth.min(rows.len())], &merges);
+    let records: Vec<StringRecord> = rows
+        .iter()
+        .skip(depth)
+        .filter(|r| r.iter().any(|c| !c.is_empty()))
+        .map(|r| r.iter().collect())
+        .collect();
+
+    let format = match workbook {
+        Sheets::Ods(_) => "OpenDocument spreadsheet",
+        _ => "Excel workbook",
+    };
+    let mut dataset = Dataset::new(file_path, format, headers, records);
+    dataset.info.push(("Sheet".to_string(), sheet));
+    dataset.info.push(("Sheets".to_string(), sheets.join(", ")));
+    dataset.sheets = sheets;
+    Ok(dataset)
+}
+
+fn open(file_path: &str) -> Result<Sheets<BufReader<File>>, String> {
+    open_workbook_auto(file_path).map_err(|e| format!("could not open {}: {}", file_path, e))
+}
+
+/// Merged regions relative to the top left corner of the used range. Only
+/// xlsx and xls expose them, other formats fall back to guessing.
+fn merged_cells(workbook: &mut Sheets<BufReader<File>>, sheet: &str, range: &Range<Data>) -> Option<Vec<Dimensions>> {
+    let merges = match workbook {
+        Sheets::Xlsx(xlsx) => xlsx.worksheet_merge_cells(sheet).and_then(|r| r.ok()),
+        Sheets::Xls(xls) => xls.worksheet_merge_cells(sheet),
+        _ => None,
+    }?;
+    let (top, left) = range.start().unwrap_or((0, 0));
+    Some(
+        merges
+            .into_iter()
+            .filter(|d| d.start.0 >= top && d.start.1 >= left)
+            .map(|d| Dimensions {
+                start: (d.start.0 - top, d.start.1 - left),
+                end: (d.end.0 - top, d.end.1 - left),
+            })
+            .collect(),
+    )
+}
+
+fn header_depth(rows: &[Vec<String>], merges: &Option<Vec<Dimensions>>) -> usize {
+    match merges {
+        Some(merges) => merges
+            .iter()
+            .filter(|d| d.start.0 == 0)
+            .map(|d| match d.end.1 > d.start.1 {
+                // a group spanning several columns has its sub headers in the next row
+                true => d.end.0 as usize + 2,
+                false => d.end.0 as usize + 1,
+            })
+            .max()
+            .unwrap_or(1)
+            .min(MAX_HEADER_ROWS),
+        None => {
+            // without merge information, a gappy first row over a complete second row is a grouped header
+            let gappy = rows.first().map(|r| r.iter().skip(1).any(|c| c.is_empty())).unwrap_or(false);
+            let complete = rows
+                .get(1)
+                .map(|r| r.iter().all(|c| !c.is_empty() && c.parse::<f64>().is_err()))
+                .unwrap_or(false);
+            if gappy && complete { 2 } else { 1 }
+        }
+    }
+}
+
+fn flatten_headers(rows: &[Vec<String>], merges: &Option<Vec<Dimensions>>) -> Vec<String> {
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut filled: Vec<Vec<String>> = rows.to_vec();
+    for (r, row) in filled.iter_mut().enumerate() {
+        row.resize(width, String::new());
+        for c in 1..width {
+            if !row[c].is_empty() {
+                continue;
+            }
+            let spanned = match merges {
+                Some(merges) => merges.iter().any(|d| {
+                    (d.start.0 as usize..=d.end.0 as usize).contains(&r)
+                        && (d.start.1 as usize) < c
+                        && c <= d.end.1 as usize
+                }),
+                None => r + 1 < rows.len(),
+            };
+            if spanned {
+                row[c] = row[c - 1].clone();
+            }
+        }
+    }
+
+    (0..width)
+        .map(|c| {
+            let mut parts: Vec<&str> = Vec::new();
+            for row in &filled {
+                let part = row[c].trim();
+                if !part.is_empty() && parts.last() != Some(&part) {
+                    parts.push(part);
+                }
+            }
+            match parts.is_empty() {
+                true => format!("column {}", c + 1),
+                false => parts.join(SEPARATOR),
+            }
+        })
+        .collect()
+}
+
+fn format_cell(cell: &Data, format: Option<&NumberFormat>) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::Int(i) if format.is_some() => format.map(|n| n.format(*i as f64)).unwrap_or_default(),
+        Data::Float(f) if format.is_some() => format.map(|n| n.format(*f)).unwrap_or_default(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) => format_number(*f),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(d) if d.is_duration() => d
+            .as_duration()
+            .map(|d| format!("{:02}:{:02}:{:02}", d.num_hours(), d.num_minutes() % 60, d.num_seconds() % 60))
+            .unwrap_or_default(),
+        Data::DateTime(d) => match d.as_datetime() {
+            Some(dt) if dt.time() == chrono::NaiveTime::MIN => dt.format("%Y-%m-%d").to_string(),
+            Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => d.as_f64().to_string(),
+        },
+        Data::Error(e) => format!("#{:?}", e),
+    }
+}
+
+// whole numbers are stored as floats by excel, show them the way they were typed
+fn format_number(f: f64) -> String {
+    if f.fract() == 0.0 && f.abs() < 1e15 {
+        format!("{}", f as i64)
+    } else {
+        f.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::{ExcelDateTime, ExcelDateTimeType};
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect()
+    }
+
+    fn merge(start: (u32, u32), end: (u32, u32)) -> Dimensions {
+        Dimensions { start, end }
+    }
+
+    #[test]
+    fn merged_group_headers_are_joined_with_their_sub_headers() {
+        let header = rows(&[&["region", "sales", "", "notes"], &["", "q1", "q2", ""]]);
+        let merges = Some(vec![merge((0, 0), (1, 0)), merge((0, 1), (0, 2)), merge((0, 3), (1, 3))]);
+        assert_eq!(header_depth(&header, &merges), 2);
+        assert_eq!(flatten_headers(&header, &merges), vec!["region", "sales/q1", "sales/q2", "notes"]);
+    }
+
+    #[test]
+    fn without_merges_a_gappy_first_row_groups_the_second() {
+        let header = rows(&[&["id", "sales", "", "cost", ""], &["id", "q1", "q2", "q1", "q2"]]);
+        assert_eq!(header_depth(&header, &None), 2);
+        assert_eq!(flatten_headers(&header, &None), vec!["id", "sales/q1", "sales/q2", "cost/q1", "cost/q2"]);
+        // a single row is taken as it is
+        assert_eq!(header_depth(&rows(&[&["a", "", "c"], &["1", "2", "3"]]), &None), 1);
+        assert_eq!(flatten_headers(&rows(&[&["a", "", "c"]]), &None), vec!["a", "column 2", "c"]);
+    }
+
+    #[test]
+    fn dates_drop_a_midnight_time() {
+        let date = |value, kind| Data::DateTime(ExcelDateTime::new(value, kind, false));
+        assert_eq!(format_cell(&date(45292.0, ExcelDateTimeType::DateTime), None), "2024-01-01");
+        assert_eq!(format_cell(&date(45292.75, ExcelDateTimeType::DateTime), None), "2024-01-01 18:00:00");
+        assert_eq!(format_cell(&date(1.5, ExcelDateTimeType::TimeDelta), None), "36:00:00");
+    }
+
+    #[test]
+    fn numbers_show_as_typed_unless_formatted() {
+        assert_eq!(format_cell(&Data::Float(3.0), None), "3");
+        assert_eq!(format_cell(&Data::Float(2.5), None), "2.5");
+        assert_eq!(format_cell(&Data::Float(0.25), Some(&NumberFormat::Percent(0))), "25%");
+        assert_eq!(format_cell(&Data::Int(-2), Some(&NumberFormat::Percent(1))), "-200.0%");
+    }
+}
@@ -0,0 +1,146 @@
+use csv::StringRecord;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+
+// nested keys are joined the same way our csv exports name them, e.g. `name/first`
+const SEPARATOR: &str = "/";
+
+
+/// Reads a JSON array or a JSON Lines (NDJSON) file into the same
+/// (headers, records) shape the csv reader produces. Nested objects and
+/// arrays are flattened into `parent/child` columns and the columns are the
+/// union of the keys of every record, in the order they were first seen.
+/// Lines of a JSON Lines file that do not parse are skipped and counted.
+pub fn read(file_path: &str) -> Result<(Vec<String>, Vec<StringRecord>, usize), String> {
+    let content = fs::read_to_string(file_path).map_err(|e| format!("could not read {}: {}", file_path, e))?;
+    Ok(parse(&content))
+}
+
+fn parse(content: &str) -> (Vec<String>, Vec<StringRecord>, usize) {
+    let (values, skipped) = parse_values(content);
+
+    let mut headers: Vec<String> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut rows: Vec<HashMap<usize, String>> = Vec::new();
+    for value in values {
+        let mut fields: Vec<(String, String)> = Vec::new();
+        flatten("", &value, &mut fields);
+        let mut row = HashMap::new();
+        for (key, field) in fields {
+            let index = *positions.entry(key.clone()).or_insert_with(|| {
+                headers.push(key);
+                headers.len() - 1
+            });
+            row.insert(index, field);
+        }
+        rows.push(row);
+    }
+
+    let records = rows
+        .iter()
+        .map(|row| {
+            (0..headers.len())
+                .map(|i| row.get(&i).map(|s| s.as_str()).unwrap_or(""))
+                .collect::<StringRecord>()
+        })
+        .collect();
+    (headers, records, skipped)
+}
+
+fn parse_values(content: &str) -> (Vec<Value>, usize) {
+    // a .json file may hold a single array/object, otherwise treat it as one document per line
+    if let Ok(value) = serde_json::from_str::<Value>(content) {
+        return match value {
+            Value::Array(items) => (items, 0),
+            other => (vec![other], 0),
+        };
+    }
+    let mut skipped = 0;
+    let values = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                skipped += 1;
+                None
+            }
+        })
+        .collect();
+    (values, skipped)
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => flatten_object(prefix, map, out),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(&join(prefix, &i.to_string()), item, out);
+            }
+        }
+        Value::Null => out.push((column_name(prefix), String::new())),
+        Value::String(s) => out.push((column_name(prefix), s.clone())),
+        other => out.push((column_name(prefix), other.to_string())),
+    }
+}
+
+fn flatten_object(prefix: &str, map: &Map<String, Value>, out: &mut Vec<(String, String)>) {
+    for (key, value) in map {
+        flatten(&join(prefix, key), value, out);
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    match prefix.is_empty() {
+        true => key.to_string(),
+        false => format!("{}{}{}", prefix, SEPARATOR, key),
+    }
+}
+
+// scalars at the top level (e.g. `[1, 2, 3]`) end up in a single `value` column
+fn column_name(prefix: &str) -> String {
+    match prefix.is_empty() {
+        true => "value".to_string(),
+        false => prefix.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(records: &[StringRecord]) -> Vec<Vec<&str>> {
+        records.iter().map(|r| r.iter().collect()).collect()
+    }
+
+    #[test]
+    fn nested_objects_and_arrays_become_slash_columns() {
+        let (headers, records, _) = parse(r#"[{"id": 1, "name": {"first": "Ann", "last": null}, "tags": ["a", "b"]}]"#);
+        assert_eq!(headers, vec!["id", "name/first", "name/last", "tags/0", "tags/1"]);
+        assert_eq!(rows(&records), vec![vec!["1", "Ann", "", "a", "b"]]);
+    }
+
+    #[test]
+    fn columns_are_the_union_of_the_keys_in_first_seen_order() {
+        let (headers, records, _) = parse("{\"b\": 1, \"a\": 2}\n{\"c\": true, \"a\": 3}\n");
+        assert_eq!(headers, vec!["b", "a", "c"]);
+        assert_eq!(rows(&records), vec![vec!["1", "2", ""], vec!["", "3", "true"]]);
+    }
+
+    #[test]
+    fn lines_that_do_not_parse_are_skipped_and_counted() {
+        let (headers, records, skipped) = parse("{\"a\": 1}\nnot json\n\n{\"a\": 2}\n{\"a\":\n");
+        assert_eq!(headers, vec!["a"]);
+        assert_eq!(rows(&records), vec![vec!["1"], vec!["2"]]);
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn top_level_scalars_go_into_a_value_column() {
+        let (headers, records, skipped) = parse("[1, \"two\", null]");
+        assert_eq!(headers, vec!["value"]);
+        assert_eq!(rows(&records), vec![vec!["1"], vec!["two"], vec![""]]);
+        assert_eq!(skipped, 0);
+    }
+}
@@ -0,0 +1,50 @@
+use csv::StringRecord;
+
+pub mod json;
+pub mod columnar;
+pub mod excel;
+pub mod cell_formats;
+pub mod sqlite;
+
+
+/// Everything a loader hands back to `App::new`.
+pub struct Dataset {
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    // numeric columns according to the file's own schema, None means infer them from the values
+    pub numeric: Option<Vec<bool>>,
+    pub info: Vec<(String, String)>,
+    pub source: Option<columnar::ColumnarSource>,
+    // sheets of a workbook or tables of a database, empty for single table formats
+    pub sheets: Vec<String>,
+}
+
+impl Dataset {
+    pub fn new(file_path: &str, format: &str, headers: Vec<String>, records: Vec<StringRecord>) -> Dataset {
+        let size = std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+        let info = vec![
+            ("Format".to_string(), format.to_string()),
+            ("Path".to_string(), file_path.to_string()),
+            ("Size".to_string(), format!("{} bytes", size)),
+            ("Rows".to_string(), records.len().to_string()),
+            ("Columns".to_string(), headers.len().to_string()),
+        ];
+        Dataset { headers, records, numeric: None, info, source: None, sheets: vec![] }
+    }
+
+    /// Reads whatever a lazy source has not handed out yet, for headless use.
+    pub fn load_all(&mut self) -> Result<(), String> {
+        self.load_rows(usize::MAX)
+    }
+
+    /// Reads chunks of a lazy source until at least `rows` records are in
+    /// memory or the source is done.
+    pub fn load_rows(&mut self, rows: usize) -> Result<(), String> {
+        if let Some(source) = self.source.as_mut() {
+            while source.has_more() && self.records.len() < rows {
+                self.records.extend(source.load_next()?);
+            }
+        }
+        Ok(())
+    }
+}
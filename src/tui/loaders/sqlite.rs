@@ -0,0 +1,97 @@
+use csv::StringRecord;
+use rusqlite::{types::ValueRef, Connection, OpenFlags};
+use std::io::Read;
+use super::Dataset;
+
+const MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// Column names, rows and which columns are numeric.
+pub type ResultSet = (Vec<String>, Vec<StringRecord>, Vec<bool>);
+
+
+/// Sniffs the sqlite header so databases are recognised whatever their extension.
+pub fn is_database(file_path: &str) -> bool {
+    let mut header = [0u8; 16];
+    std::fs::File::open(file_path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| &header == MAGIC)
+        .unwrap_or(false)
+}
+
+/// Opens the first table of the database; the others are listed in the menu.
+pub fn read(file_path: &str) -> Result<Dataset, String> {
+    match tables(file_path).first() {
+        Some(table) => read_table(file_path, table),
+        None => Ok(Dataset::new(file_path, "SQLite database", vec![], vec![])),
+    }
+}
+
+/// Reads a whole table or view, which fails e.g. for a view over a table
+/// that no longer exists.
+pub fn read_table(file_path: &str, table: &str) -> Result<Dataset, String> {
+    let sql = format!("SELECT * FROM \"{}\"", table.replace('"', "\"\""));
+    let mut dataset = query(file_path, &sql).map_err(|e| format!("could not read {}: {}", table, e))?;
+    dataset.info.retain(|(k, _)| k != "Query");
+    dataset.info.push(("Table".to_string(), table.to_string()));
+    Ok(dataset)
+}
+
+/// Runs a statement against the database (read only) and returns its result set.
+pub fn query(file_path: &str, sql: &str) -> rusqlite::Result<Dataset> {
+    let conn = open(file_path)?;
+    let (headers, records, numeric) = run(&conn, sql)?;
+    let mut dataset = Dataset::new(file_path, "SQLite database", headers, records);
+    dataset.numeric = Some(numeric);
+    dataset.info.push(("Query".to_string(), sql.to_string()));
+    dataset.sheets = tables(file_path);
+    Ok(dataset)
+}
+
+/// Runs `sql` on an open connection. Columns are numeric when every non null
+/// value sqlite hands back is an integer or a real.
+pub fn run(conn: &Connection, sql: &str) -> rusqlite::Result<ResultSet> {
+    let mut stmt = conn.prepare(sql)?;
+    let headers: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+    let mut numeric = vec![true; headers.len()];
+    let mut seen = vec![false; headers.len()];
+    let mut records: Vec<StringRecord> = Vec::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let mut record = StringRecord::new();
+        for i in 0..headers.len() {
+            let value = row.get_ref(i)?;
+            let text = match value {
+                ValueRef::Null => String::new(),
+                ValueRef::Integer(v) => v.to_string(),
+                ValueRef::Real(v) => v.to_string(),
+                ValueRef::Text(t) => String::from_utf8_lossy(t).to_string(),
+                ValueRef::Blob(b) => format!("<{} bytes>", b.len()),
+            };
+            if !matches!(value, ValueRef::Null) {
+                seen[i] = true;
+                numeric[i] &= matches!(value, ValueRef::Integer(_) | ValueRef::Real(_));
+            }
+            record.push_field(&text);
+        }
+        records.push(record);
+    }
+    let numeric = numeric.iter().zip(seen).map(|(n, s)| *n && s).collect();
+    Ok((headers, records, numeric))
+}
+
+/// Lists tables and views, skipping sqlite's internal ones.
+pub fn tables(file_path: &str) -> Vec<String> {
+    let Ok(conn) = open(file_path) else { return vec![] };
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY type, name",
+    ) else {
+        return vec![];
+    };
+    stmt.query_map([], |row| row.get::<_, String>(0))
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default()
+}
+
+fn open(file_path: &str) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(file_path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+}
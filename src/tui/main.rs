@@ -5,14 +5,22 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{prelude::*, widgets::*};
-use crate::tui::models;
+use crate::tui::{chart_view::Axis, models, session::{self, Session}};
 use crate::components::{charts, menu, table, utils};
 
 use color_eyre::Result;
+use std::time::Duration;
 
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 
-pub fn main(file_path: String) -> Result<(), Box<dyn Error>> {
+
+pub fn main(file_paths: Vec<String>, follow: bool) -> Result<(), Box<dyn Error>> {
+    show(Session::new(file_paths, follow)?)
+}
+
+/// Runs the TUI on the files of `session` until the user quits.
+pub fn show(session: Session) -> Result<(), Box<dyn Error>> {
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -20,9 +28,7 @@ pub fn main(file_path: String) -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // create app and run it
-    let app = models::App::new(file_path);
-    let _ = run_app(&mut terminal, app);
+    let _ = run_app(&mut terminal, session);
 
     // restore terminal
     disable_raw_mode()?;
@@ -37,14 +43,50 @@ pub fn main(file_path: String) -> Result<(), Box<dyn Error>> {
 }
 
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: models::App) -> io::Result<()> {
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut session: Session) -> io::Result<()> {
     loop {
-        terminal.draw(|f| ui(f, &mut app))?;
+        session.handle_request();
+        terminal.draw(|f| ui(f, session.app()))?;
 
+        // look for new records or rewritten files between key presses
+        if session.is_polling() && !event::poll(POLL_INTERVAL)? {
+            session.poll();
+            continue;
+        }
+        let app = session.app();
         if let Event::Key(key) = event::read()? {
             if key.kind == KeyEventKind::Press {
                 use KeyCode::*;
+                app.status.clear();
+                if let Some(prompt) = app.prompt.as_mut() {
+                    match key.code {
+                        Enter => app.submit_prompt(),
+                        Esc => app.prompt = None,
+                        Backspace => {
+                            prompt.input.pop();
+                        }
+                        Tab if prompt.kind == models::PromptKind::Open => {
+                            let (input, candidates) = session::complete(&prompt.input);
+                            prompt.input = input;
+                            prompt.hint = candidates.join("  ");
+                        }
+                        Char(c) => prompt.input.push(c),
+                        _ => {}
+                    }
+                    continue;
+                }
+                if app.sheet_picker.is_some() {
+                    match key.code {
+                        Char('j') | Down => app.move_sheet_picker(true),
+                        Char('k') | Up => app.move_sheet_picker(false),
+                        Enter => app.select_sheet(),
+                        Esc | Char('q') => app.sheet_picker = None,
+                        _ => {}
+                    }
+                    continue;
+                }
                 match key.code {
+                    Esc if app.selection_anchor.is_some() => app.selection_anchor = None,
                     Char('q') | Esc => return Ok(()),
                     Char('j') | Down => app.next(),
                     Char('k') | Up => app.previous(),
@@ -52,6 +94,46 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: models::App) -> io::
                     Char('h') | Left => app.previous_color(),
                     Char('t') | Enter => app.next_menu(),
                     Char('b') | BackTab => app.previous_menu(),
+                    Char('L') => app.next_column(),
+                    Char('H') => app.previous_column(),
+                    Char('v') => app.toggle_visual(),
+                    Char('y') => app.yank(),
+                    Char('Y') => app.yank_row(),
+                    Char('f') => app.toggle_yank_format(),
+                    Char('S') => app.open_sheet_picker(),
+                    Char('F') => app.open_prompt(models::PromptKind::Open),
+                    Char('M') => app.open_prompt(models::PromptKind::Join),
+                    Char('A') => app.open_prompt(models::PromptKind::Derive),
+                    Char('x') => app.open_prompt(models::PromptKind::Transform),
+                    Char('u') => app.undo(),
+                    Char('/') => app.open_prompt(models::PromptKind::Filter),
+                    Char('P') => app.open_prompt(models::PromptKind::Pipeline),
+                    Char('s') => app.open_prompt(models::PromptKind::Sql),
+                    Char('g') => app.toggle_group_key(),
+                    Char('p') => app.open_prompt(models::PromptKind::Pivot),
+                    Char('e') => app.open_prompt(models::PromptKind::Export),
+                    Char('D') => app.toggle_duplicates(),
+                    Char('O') => app.toggle_outliers(),
+                    Char('B') => app.open_prompt(models::PromptKind::BoxPlot),
+                    Char('T') => app.open_prompt(models::PromptKind::TimeSeries),
+                    Char('z') => app.toggle_outlier_method(),
+                    Char('c') if app.tab == "Statistics" => app.correlation.toggle_method(),
+                    Char('J') if app.tab == "Statistics" => app.correlation.move_cursor(1, 0),
+                    Char('K') if app.tab == "Statistics" => app.correlation.move_cursor(-1, 0),
+                    Char('J') if app.tab == "Visualization" => app.chart_view.pan(Axis::Y, -1.0),
+                    Char('K') if app.tab == "Visualization" => app.chart_view.pan(Axis::Y, 1.0),
+                    Char('+') | Char('=') if app.tab == "Visualization" => app.chart_view.zoom_in(None),
+                    Char('-') if app.tab == "Visualization" => app.chart_view.zoom_out(None),
+                    Char('>') if app.tab == "Visualization" => app.chart_view.zoom_in(Some(Axis::X)),
+                    Char('<') if app.tab == "Visualization" => app.chart_view.zoom_out(Some(Axis::X)),
+                    Char('}') if app.tab == "Visualization" => app.chart_view.zoom_in(Some(Axis::Y)),
+                    Char('{') if app.tab == "Visualization" => app.chart_view.zoom_out(Some(Axis::Y)),
+                    Char('0') if app.tab == "Visualization" => app.chart_view.reset(),
+                    Char('a') if app.tab == "Visualization" => app.open_prompt(models::PromptKind::Axes),
+                    Char('o') if app.tab == "Group By" => app.group_by.sort_by_column(),
+                    Char(c @ '1'..='7') if app.tab == "Group By" => {
+                        app.group_by.toggle_aggregate(c as usize - '1' as usize)
+                    }
                     _ => {}
                 }
             }
@@ -101,16 +183,42 @@ pub fn ui(f: &mut Frame, app: &mut models::App) {
             utils::scroll_bar::render(f, &mut app.scroll_state, inner_layout[2]);
         },
         "Visualization" => {
+            let [charts_area, box_plot] = Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(inner_layout[2]);
             let horizontal = Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]);
-            let [line_chart, scatter]  = horizontal.areas(inner_layout[2]);
-            charts::line_chart::render(f, app, line_chart);
+            let [line_chart, scatter]  = horizontal.areas(charts_area);
+            // a time series, once set up, takes the place of the line chart
+            match app.time_series.spec {
+                Some(_) => charts::time_series::render(f, app, line_chart),
+                None => charts::line_chart::render(f, app, line_chart),
+            }
             charts::scatter_plot::render(f, app, scatter);
+            charts::box_plot::render(f, app, box_plot);
         },
         "Statistics" => {
-            table::statistics::render(f, app, inner_layout[2]);
-            utils::scroll_bar::render(f, &mut app.scroll_state, inner_layout[2]);
+            let [stats_area, correlation_area] = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(inner_layout[2]);
+            let [matrix_area, pair_area] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(correlation_area);
+            table::statistics::render(f, app, stats_area);
+            utils::scroll_bar::render(f, &mut app.scroll_state, stats_area);
+            table::correlation::render(f, app, matrix_area);
+            charts::scatter_plot::render_pair(f, app, pair_area);
+        },
+        "Data Quality" => {
+            table::quality::render(f, app, inner_layout[2]);
+        },
+        "Group By" => {
+            let [table_area, chart_area] = Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(inner_layout[2]);
+            table::group_by::render(f, app, table_area);
+            charts::bar_chart::render(f, app, chart_area);
+        },
+        "Pivot" => {
+            table::pivot::render(f, app, inner_layout[2]);
+        },
+        "File Info" => {
+            table::file_info::render(f, app, inner_layout[2]);
+        },
+        "Extras" => {
+            table::sql_console::render(f, app, inner_layout[2]);
         },
-        "Extras" => {},
         _ => {}
     }
 
@@ -119,4 +227,7 @@ pub fn ui(f: &mut Frame, app: &mut models::App) {
     utils::scroll_bar::render(f, &mut app.menu_scroll_state, inner_layout[0]);
     // footer
     utils::footer::render(f, app, outer_layout[2]);
+    utils::prompt::render(f, app, outer_layout[2]);
+    // popups
+    menu::sheets::render(f, app, f.size());
 }
\ No newline at end of file
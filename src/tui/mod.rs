@@ -1,4 +1,29 @@
 pub mod main;
 pub mod models;
 pub mod stats;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod clipboard;
+pub mod loaders;
+pub mod sql;
+pub mod group_by;
+pub mod pivot;
+pub mod correlation;
+pub mod quality;
+pub mod duplicates;
+pub mod view;
+pub mod outliers;
+pub mod box_plot;
+pub mod time_series;
+pub mod chart_view;
+pub mod plot;
+pub mod chart_export;
+pub mod follow;
+pub mod reload;
+pub mod session;
+pub mod diff;
+pub mod join;
+pub mod concat;
+pub mod expr;
+pub mod derived;
+pub mod transform;
+pub mod pipeline;
@@ -1,8 +1,31 @@
 use style::palette::tailwind;
 use ratatui::{prelude::*, widgets::*};
 use crate::tui::utils::*;
+use crate::tui::loaders::{self, columnar::{ColumnarSource, Format}, Dataset};
+use crate::tui::clipboard::{self, YankFormat};
+use crate::tui::sql::{self, SqlConsole};
+use crate::tui::group_by::GroupBy;
+use crate::tui::pivot::{Pivot, PivotSpec};
+use crate::tui::correlation::Correlation;
+use crate::tui::quality::{self, ColumnProfile};
+use crate::tui::duplicates::{self, Keep};
+use crate::tui::view::{self, Filter, RowView};
+use crate::tui::outliers::Outliers;
+use crate::tui::box_plot::BoxPlot;
+use crate::tui::time_series::{TimeSeries, TimeSeriesSpec};
+use crate::tui::chart_view::{Axis, ChartView};
+use crate::tui::{chart_export, plot};
+use crate::tui::follow::Follow;
+use crate::tui::reload::{self, Watcher};
+use crate::tui::session::FileRequest;
+use crate::tui::diff::Diff;
+use crate::tui::join::{self, JoinSpec};
+use crate::tui::derived::Derived;
+use crate::tui::pipeline;
+use crate::tui::transform::{self, Snapshot, Step};
 use csv::{ReaderBuilder, StringRecord};
 use std::fs::File;
+use std::str::FromStr;
 
 const PALETTES: [tailwind::Palette; 4] = [
     tailwind::BLUE,
@@ -12,6 +35,13 @@ const PALETTES: [tailwind::Palette; 4] = [
 ];
 
 const ITEM_HEIGHT: usize = 4;
+const TABS: [&str; 8] = ["Data Explorer", "Visualization", "Statistics", "Data Quality", "Group By", "Pivot", "File Info", "Extras"];
+// rows kept loaded below the selection for files that are read chunk by chunk
+const WINDOW_ROWS: usize = 100;
+// latest rows plotted while following a growing file
+const FOLLOW_WINDOW: usize = 500;
+// File Info entry counting the lines of a JSON Lines file that did not parse
+const SKIPPED_LINES: &str = "Skipped lines";
 
 pub struct TableColors {
     pub buffer_bg: Color,
@@ -22,6 +52,14 @@ pub struct TableColors {
     pub normal_row_color: Color,
     pub alt_row_color: Color,
     pub footer_border_color: Color,
+    // light to dark shades of the palette for heatmaps
+    pub heatmap: [Color; 5],
+    // outlier cells and points, apart from every palette
+    pub outlier: Color,
+    // rows and cells of a diff
+    pub added: Color,
+    pub removed: Color,
+    pub changed: Color,
 }
 
 impl TableColors {
@@ -35,11 +73,61 @@ impl TableColors {
             normal_row_color: tailwind::SLATE.c950,
             alt_row_color: tailwind::SLATE.c900,
             footer_border_color: color.c400,
+            heatmap: [color.c100, color.c300, color.c500, color.c700, color.c900],
+            outlier: tailwind::FUCHSIA.c400,
+            added: tailwind::GREEN.c400,
+            removed: tailwind::RED.c400,
+            changed: tailwind::AMBER.c400,
         }
     }
 }
 
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PromptKind {
+    Sql,
+    Pivot,
+    Export,
+    Duplicates,
+    BoxPlot,
+    TimeSeries,
+    Axes,
+    Open,
+    Join,
+    Derive,
+    Transform,
+    Filter,
+    Pipeline,
+}
+
+/// A line of text typed by the user, shown in place of the footer.
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+    // e.g. the paths a partly typed one could be completed to
+    pub hint: String,
+}
+
+impl PromptKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PromptKind::Sql => "SQL",
+            PromptKind::Pivot => "Pivot (rows, columns[, aggregate, value])",
+            PromptKind::Export => "Export to",
+            PromptKind::Duplicates => "Duplicates on columns (empty for whole rows)",
+            PromptKind::BoxPlot => "Box plot (value by category, empty for every column)",
+            PromptKind::Axes => "Axes (x|y <min> <max>, log [x|y], fit)",
+            PromptKind::Open => "Open file (Tab completes)",
+            PromptKind::Derive => "Add column (name = expression, e.g. gross = amount * 1.19)",
+            PromptKind::Filter => "Keep rows where (expression, e.g. amount > 100 and state != \"CA\")",
+            PromptKind::Pipeline => "Save pipeline to (.toml or .json)",
+            PromptKind::Transform => "Transform (trim, upper, cast integer, replace <regex> => <text>, split ,, fill mean, round 2, ...)",
+            PromptKind::Join => "Join (path on key[=other key][, ...] [inner|left|right|full])",
+            PromptKind::TimeSeries => "Time series (date, value[, daily|weekly|monthly|yearly[, aggregate]][, format])",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Data {
     pub data: Vec<f64>,
@@ -55,7 +143,6 @@ pub struct App<'a> {
     // app state
     pub app_state: TableState,
     pub items: Vec<StringRecord>,
-    pub longest_item_lens: Vec<u16>, // order is (name, address, email)
     pub scroll_state: ScrollbarState,
     pub colors: TableColors,
     pub color_index: usize,
@@ -64,17 +151,61 @@ pub struct App<'a> {
     pub grouped_headers: Vec<(String, String)>,
     pub plot_data: Vec<Vec<(f64, f64)>>,
     pub raw_data: Vec<Data>,
-    pub stats_header: Vec<String>
+    pub stats_header: Vec<String>,
+    // cell cursor and visual selection
+    pub selected_column: usize,
+    pub selection_anchor: Option<(usize, usize)>,
+    pub yank_format: YankFormat,
+    pub status: String,
+    // file metadata and lazily read parquet/arrow chunks
+    pub file_info: Vec<(String, String)>,
+    pub numeric_columns: Option<Vec<bool>>,
+    pub source: Option<ColumnarSource>,
+    // workbook sheets and the sheet picker popup
+    pub file_path: String,
+    pub sheets: Vec<String>,
+    pub sheet_picker: Option<ListState>,
+    pub prompt: Option<Prompt>,
+    // bumped whenever `items` changes so derived state can be rebuilt lazily
+    pub generation: usize,
+    pub sql_console: SqlConsole,
+    pub group_by: GroupBy,
+    pub pivot: Pivot,
+    pub correlation: Correlation,
+    pub quality: Vec<ColumnProfile>,
+    pub quality_generation: Option<usize>,
+    // rows shown in the Data Explorer when not all of them
+    pub view: Option<RowView>,
+    pub outliers: Outliers,
+    pub box_plot: BoxPlot,
+    pub time_series: TimeSeries,
+    pub chart_view: ChartView,
+    // `--follow`: records appended to the file are read as they arrive
+    pub follow: Option<Follow>,
+    // reloads the file when it is rewritten
+    pub watch: Option<Watcher>,
+    // every open file, listed in the menu when there are several
+    pub files: Vec<String>,
+    pub file_request: Option<FileRequest>,
+    // set when the table shows the differences between two files
+    pub diff: Option<Diff>,
+    // derived columns and transforms, in the order they were applied
+    pub steps: Vec<Step>,
+    // the table before the first step, what undo and reloads replay them on
+    pub base: Option<Snapshot>,
 }
 
-fn parse_records(records: &Vec<StringRecord>, headers: &Vec<String>) -> (Vec<(String, String)>, Vec<Vec<(f64, f64)>>, Vec<Data>) {
+type ParsedRecords = (Vec<(String, String)>, Vec<Vec<(f64, f64)>>, Vec<Data>);
+
+fn parse_records(records: &[StringRecord], headers: &[String], numeric: Option<&[bool]>) -> ParsedRecords {
     let mut res: Vec<Data> = [].to_vec();
     let mut column_vectors: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
 
-    for (_, record) in records.iter().enumerate() {
-        for (index, field) in record.iter().enumerate() {
-            // Store the field in the corresponding column vector
-            column_vectors[index].push(field.to_string());
+    for record in records.iter() {
+        // Store the field in the corresponding column vector, cells past
+        // the headers have no column to go in
+        for (column_vector, field) in column_vectors.iter_mut().zip(record.iter()) {
+            column_vector.push(field.to_string());
         }
     }
 
@@ -86,17 +217,21 @@ fn parse_records(records: &Vec<StringRecord>, headers: &Vec<String>) -> (Vec<(St
      // Print sorted vectors
      let mut digit_headers: Vec<String> = Vec::new();
      for (index, column_vector) in column_vectors.iter().enumerate() {
-        if is_valid_float(column_vector[0].as_str()) {
+        let is_numeric = match numeric {
+            Some(flags) => flags.get(index).copied().unwrap_or(false),
+            None => column_vector.first().map(|v| is_valid_float(v)).unwrap_or(false),
+        };
+        if is_numeric {
             res.push(Data { data:  parse_strings_to_floats(column_vector), label: headers.get(index).unwrap().to_string() });
             digit_headers.push(headers.get(index).unwrap().to_string());
         }
     }
 
     let mut fin: Vec<Vec<(f64, f64)>> = [].to_vec();
-    let mut aa: Vec<f64> = Vec::new();
+    let aa: Vec<f64> = Vec::new();
 
-    if res.len() != 0 {
-        if res.len() % 2 != 0 {
+    if !res.is_empty() {
+        if !res.len().is_multiple_of(2) {
             let mut current_number = 0.0;
             let mut aa: Vec<f64> = Vec::new();
             while current_number <= res[0].data.len() as f64 {
@@ -115,7 +250,7 @@ fn parse_records(records: &Vec<StringRecord>, headers: &Vec<String>) -> (Vec<(St
         digit_headers.push("Increasing counter".to_string());
         let grouped_vectors: Vec<Vec<Vec<f64>>> = new_vec.chunks_exact(2).map(|chunk| chunk.to_vec()).collect();
         for g in grouped_vectors {
-            let merged_vector: Vec<(f64, f64)> = g[0].clone().into_iter().zip(g[1].clone().into_iter()).collect();
+            let merged_vector: Vec<(f64, f64)> = g[0].clone().into_iter().zip(g[1].clone()).collect();
             fin.push(merged_vector)
         }
     }
@@ -130,14 +265,14 @@ fn parse_records(records: &Vec<StringRecord>, headers: &Vec<String>) -> (Vec<(St
         .collect();
     
  
-    return (grouped_headers, fin, res)
+    (grouped_headers, fin, res)
 }
 
 fn is_valid_float(s: &str) -> bool {
     s.trim().parse::<f64>().is_ok()
 }
 
-fn parse_strings_to_floats(strings: &Vec<String>) -> Vec<f64> {
+fn parse_strings_to_floats(strings: &[String]) -> Vec<f64> {
     strings
         .iter()
         .filter_map(|s| s.parse::<f64>().ok())
@@ -145,25 +280,30 @@ fn parse_strings_to_floats(strings: &Vec<String>) -> Vec<f64> {
 }
 
 impl<'a> App<'a> {
-    pub fn new(file_path: String) -> App<'a> {
+    pub fn new(file_path: String) -> Result<App<'a>, String> {
+        let dataset = get_attrs(file_path.clone())?;
+        Ok(App::with_dataset(file_path, dataset))
+    }
+
+    /// An app showing `dataset`, e.g. one that was not read from a file as is.
+    pub fn with_dataset(file_path: String, dataset: Dataset) -> App<'a> {
         // let data_vec = generate_fake_names();
         let mut stats_headers = vec![
             "S/N".to_string(), "Measurement".to_string(),
         ];
-        let (headers, vals) = get_attrs(file_path);
-        let menu_items = vec!["Data Explorere".to_string(), "Visualization".to_string(), "Statistics".to_string(), "Extras".to_string()];
-        let (grouped_headers, plot_data, raw_data) = parse_records(&vals, &headers);
+        let (headers, vals) = (dataset.headers, dataset.records);
+        let menu_items: Vec<String> = TABS.iter().map(|t| t.to_string()).collect();
+        let (grouped_headers, plot_data, raw_data) = parse_records(&vals, &headers, dataset.numeric.as_deref());
         for d in raw_data.clone() {
             stats_headers.push(d.label)
         }
-        App {
+        let mut app = App {
             menu_state: TableState::default().with_selected(0),
             menu_items: menu_items.clone(),
             menu_scroll_state: ScrollbarState::new((menu_items.len() - 1) * ITEM_HEIGHT),
             longest_menu_item_len: menu_item_len_calculator(&menu_items),
             app_state: TableState::default().with_selected(0),
-            longest_item_lens: constraint_len_calculator(&vals),
-            scroll_state: ScrollbarState::new((vals.len() - 1) * ITEM_HEIGHT),
+            scroll_state: ScrollbarState::new(vals.len().saturating_sub(1) * ITEM_HEIGHT),
             colors: TableColors::new(&PALETTES[0]),
             color_index: 0,
             items: vals.clone(),
@@ -173,13 +313,888 @@ impl<'a> App<'a> {
             raw_data,
             plot_data,
             stats_header: stats_headers,
+            selected_column: 0,
+            selection_anchor: None,
+            yank_format: YankFormat::Csv,
+            status: String::new(),
+            file_info: dataset.info,
+            numeric_columns: dataset.numeric,
+            source: dataset.source,
+            file_path,
+            sheet_picker: None,
+            sheets: dataset.sheets,
+            prompt: None,
+            generation: 0,
+            sql_console: SqlConsole::new(),
+            group_by: GroupBy::new(),
+            pivot: Pivot::new(),
+            correlation: Correlation::new(),
+            quality: vec![],
+            quality_generation: None,
+            view: None,
+            outliers: Outliers::new(),
+            box_plot: BoxPlot::new(),
+            time_series: TimeSeries::new(),
+            chart_view: ChartView::new(),
+            follow: None,
+            watch: None,
+            files: vec![],
+            file_request: None,
+            diff: None,
+            steps: vec![],
+            base: None,
+        };
+        app.watch = Some(Watcher::new(&app.file_path));
+        if let Some((_, skipped)) = app.file_info.iter().find(|(k, _)| k == SKIPPED_LINES) {
+            app.status = format!("skipped {} line(s) that are not valid json", skipped);
+        }
+        app.update_menu();
+        app.load_window();
+        // let the user choose which sheet of a workbook to look at
+        if app.sheets.len() > 1 && !app.is_database() {
+            app.open_sheet_picker();
+        }
+        app
+    }
+
+    /// Replaces the loaded data, e.g. with another sheet of the same workbook.
+    pub fn set_dataset(&mut self, dataset: Dataset) {
+        self.table_header = dataset.headers;
+        self.items = dataset.records;
+        self.numeric_columns = dataset.numeric;
+        self.file_info = dataset.info;
+        self.source = dataset.source;
+        self.app_state.select(Some(0));
+        self.scroll_state = self.scroll_state.position(0);
+        self.selected_column = 0;
+        self.selection_anchor = None;
+        self.view = None;
+        self.steps.clear();
+        self.base = None;
+        self.refresh_data();
+        self.load_window();
+    }
+
+    pub fn open_sheet_picker(&mut self) {
+        if self.sheets.is_empty() {
+            self.status = "this file has no sheets to choose from".to_string();
+            return;
+        }
+        self.sheet_picker = Some(ListState::default().with_selected(Some(0)));
+    }
+
+    pub fn move_sheet_picker(&mut self, forward: bool) {
+        let count = self.sheets.len();
+        if let Some(picker) = self.sheet_picker.as_mut() {
+            let i = picker.selected().unwrap_or(0);
+            picker.select(Some(match forward {
+                true => (i + 1) % count,
+                false => (i + count - 1) % count,
+            }));
+        }
+    }
+
+    pub fn select_sheet(&mut self) {
+        let Some(picker) = self.sheet_picker.take() else { return };
+        let sheet = self.sheets[picker.selected().unwrap_or(0)].clone();
+        self.open_sheet(&sheet);
+    }
+
+    fn open_sheet(&mut self, sheet: &str) {
+        let dataset = match self.is_database() {
+            true => loaders::sqlite::read_table(&self.file_path, sheet),
+            false => loaders::excel::read(&self.file_path, Some(sheet)),
+        };
+        let dataset = match dataset {
+            Ok(dataset) => dataset,
+            Err(e) => return self.status = e,
+        };
+        self.set_dataset(dataset);
+        self.status = format!("opened {}", sheet);
+    }
+
+    pub fn open_prompt(&mut self, kind: PromptKind) {
+        let hint = match kind {
+            PromptKind::Transform => match self.table_header.get(self.selected_column) {
+                Some(column) => format!(" column {} ", column),
+                None => return self.status = "there is no column to transform".to_string(),
+            },
+            _ => String::new(),
+        };
+        self.prompt = Some(Prompt { kind, input: String::new(), hint });
+    }
+
+    pub fn submit_prompt(&mut self) {
+        let Some(prompt) = self.prompt.take() else { return };
+        match prompt.kind {
+            PromptKind::Sql => self.run_sql(&prompt.input),
+            PromptKind::Pivot => self.set_pivot(&prompt.input),
+            PromptKind::Export => self.export(prompt.input.trim()),
+            PromptKind::Duplicates => match duplicates::parse_keys(&prompt.input, &self.table_header) {
+                Ok(keys) => self.set_view(Some(Filter::Duplicates(keys))),
+                Err(e) => self.status = e,
+            },
+            PromptKind::Join => self.join_file(prompt.input.trim()),
+            PromptKind::Derive => self.add_column(&prompt.input),
+            PromptKind::Transform => self.transform_column(&prompt.input),
+            PromptKind::Filter => match Step::filter(&prompt.input, &self.table_header) {
+                Ok(step) => self.apply_step(step),
+                Err(e) => self.status = e,
+            },
+            PromptKind::Pipeline => self.save_pipeline(prompt.input.trim()),
+            PromptKind::Open => self.file_request = Some(FileRequest::Open(prompt.input.trim().to_string())),
+            PromptKind::Axes => self.status = self.chart_view.command(&prompt.input).unwrap_or_else(|e| e),
+            PromptKind::TimeSeries => match TimeSeriesSpec::parse(&prompt.input, &self.table_header) {
+                Ok(spec) => {
+                    self.time_series.spec = Some(spec);
+                    self.time_series.generation = None;
+                    self.select_menu(TABS.iter().position(|t| *t == "Visualization").unwrap_or(0));
+                }
+                Err(e) => self.status = e,
+            },
+            PromptKind::BoxPlot => match BoxPlot::parse(&prompt.input, &self.table_header) {
+                Ok(split) => {
+                    self.box_plot.split = split;
+                    self.box_plot.generation = None;
+                    self.select_menu(TABS.iter().position(|t| *t == "Visualization").unwrap_or(0));
+                }
+                Err(e) => self.status = e,
+            },
+        }
+    }
+
+    fn set_pivot(&mut self, input: &str) {
+        match PivotSpec::parse(input, &self.table_header) {
+            Ok(spec) => {
+                self.pivot.spec = Some(spec);
+                self.pivot.generation = None;
+                self.select_menu(TABS.iter().position(|t| *t == "Pivot").unwrap_or(0));
+            }
+            Err(e) => self.status = e,
+        }
+    }
+
+    /// Appends a column computed from an expression over the others. It is
+    /// recomputed for rows read later, e.g. on reload or while following.
+    fn add_column(&mut self, input: &str) {
+        match Derived::parse(input, &self.table_header) {
+            Ok(derived) => self.apply_step(Step::Derive(derived)),
+            Err(e) => self.status = e,
+        }
+    }
+
+    /// Runs a transform command on the selected column.
+    fn transform_column(&mut self, input: &str) {
+        let Some(column) = self.table_header.get(self.selected_column).cloned() else {
+            return self.status = "no column to transform".to_string();
+        };
+        match Step::transform(&column, input, &self.table_header) {
+            Ok(step) => self.apply_step(step),
+            Err(e) => self.status = e,
+        }
+    }
+
+    /// Saves the steps so far, to be run on other files with `csv-grep run`.
+    fn save_pipeline(&mut self, path: &str) {
+        if self.steps.is_empty() {
+            return self.status = "nothing to save, no filters, transforms or derived columns yet".to_string();
+        }
+        self.status = match pipeline::save(path, &self.steps) {
+            Ok(()) => format!("saved {} step(s) to {}", self.steps.len(), path),
+            Err(e) => e,
+        };
+    }
+
+    /// Applies a step to the table and keeps it, to be undone or replayed on
+    /// rows read later.
+    fn apply_step(&mut self, mut step: Step) {
+        // the diff colours go by the rows and columns as they were compared
+        if self.diff.is_some() {
+            return self.status = "a diff cannot be transformed, export it and open the export instead".to_string();
+        }
+        if self.base.is_none() {
+            self.base = Some(Snapshot {
+                headers: self.table_header.clone(),
+                items: self.items.clone(),
+                numeric: self.numeric_columns.clone(),
+            });
+        }
+        let headers = self.table_header.clone();
+        step.pin(&self.table_header, &self.items);
+        match self.run_step(&step) {
+            Ok(summary) => self.status = summary,
+            Err(e) => {
+                if self.steps.is_empty() {
+                    self.base = None;
+                }
+                return self.status = e;
+            }
+        }
+        self.steps.push(step);
+        self.refresh_columns(&headers);
+    }
+
+    /// Takes back the last step by replaying the others on the table as it
+    /// was before the first one.
+    pub fn undo(&mut self) {
+        let Some(step) = self.steps.pop() else {
+            return self.status = "nothing to undo".to_string();
+        };
+        let headers = self.table_header.clone();
+        self.rebuild();
+        if self.steps.is_empty() {
+            self.base = None;
+        }
+        self.refresh_columns(&headers);
+        self.status = format!("undid {}", step.describe());
+    }
+
+    /// Replays the steps on the table as it was before the first one.
+    fn rebuild(&mut self) {
+        let Some(base) = self.base.as_ref() else { return };
+        let table = base.replay(&self.steps);
+        self.table_header = table.headers;
+        self.items = table.items;
+        self.numeric_columns = table.numeric;
+    }
+
+    fn run_step(&mut self, step: &Step) -> Result<String, String> {
+        let headers = self.table_header.clone();
+        let summary = step.apply(&mut self.table_header, &mut self.items)?;
+        if let Some(flags) = self.numeric_columns.as_mut() {
+            *flags = transform::numeric_flags(step, &headers, flags, &self.table_header, &self.items);
+        }
+        Ok(summary)
+    }
+
+    /// Recomputes everything after a step, letting go of settings that point
+    /// at columns when the columns are not the ones there were.
+    fn refresh_columns(&mut self, headers: &[String]) {
+        if headers != self.table_header {
+            self.reset_column_settings();
+        }
+        self.refresh_data();
+        self.update_view();
+    }
+
+    fn reset_column_settings(&mut self) {
+        self.selected_column = self.selected_column.min(self.table_header.len().saturating_sub(1));
+        self.view = None;
+        self.group_by = GroupBy::new();
+        self.pivot = Pivot::new();
+        self.box_plot.split = None;
+        self.time_series.spec = None;
+    }
+
+    /// Adds records read after the steps were applied, running the steps on
+    /// them alone when they can and on the whole table again otherwise, in
+    /// which case it returns true.
+    fn add_rows(&mut self, mut records: Vec<StringRecord>) -> bool {
+        let Some(base) = self.base.as_mut() else {
+            self.items.extend(records);
+            return false;
+        };
+        base.items.extend(records.iter().cloned());
+        if !self.steps.iter().all(Step::streams) {
+            self.rebuild();
+            return true;
+        }
+        let mut headers = base.headers.clone();
+        for step in &self.steps {
+            let _ = step.apply(&mut headers, &mut records);
+        }
+        self.items.extend(records);
+        false
+    }
+
+    /// Joins another file to this one; the result opens as a file of its own.
+    fn join_file(&mut self, input: &str) {
+        let spec = match JoinSpec::parse(input) {
+            Ok(spec) => spec,
+            Err(e) => return self.status = e,
+        };
+        if !std::path::Path::new(&spec.path).is_file() {
+            return self.status = format!("no such file: {}", spec.path);
+        }
+        let mut other = match get_attrs(spec.path.clone()) {
+            Ok(other) => other,
+            Err(e) => return self.status = e,
+        };
+        if let Err(e) = other.load_all() {
+            return self.status = e;
+        }
+        let joined = match join::join(&self.table_header, &self.items, &other, &spec) {
+            Ok(joined) => joined,
+            Err(e) => return self.status = e,
+        };
+        let name = format!("{} ⋈ {} ({})", sql::table_name(&self.file_path), sql::table_name(&spec.path), spec.kind);
+        self.status = format!(
+            "{} join returned {} row(s), unmatched keys: {} here, {} in {}",
+            spec.kind, joined.records.len(), joined.unmatched_left, joined.unmatched_right, spec.path,
+        );
+        let mut dataset = Dataset::new(&name, "Join", joined.headers, joined.records);
+        dataset.info.retain(|(k, _)| k != "Path" && k != "Size");
+        dataset.info.extend([
+            ("Left".to_string(), self.file_path.clone()),
+            ("Right".to_string(), spec.path.clone()),
+            ("Unmatched keys (left)".to_string(), joined.unmatched_left.to_string()),
+            ("Unmatched keys (right)".to_string(), joined.unmatched_right.to_string()),
+        ]);
+        self.file_request = Some(FileRequest::Add(name, dataset));
+    }
+
+    pub fn update_correlation(&mut self) {
+        if self.correlation.generation == Some(self.generation) {
+            return;
+        }
+        let numeric = self.numeric_column_indices();
+        self.correlation.compute(&self.table_header, &self.items, &numeric);
+        self.correlation.generation = Some(self.generation);
+    }
+
+    pub fn update_quality(&mut self) {
+        if self.quality_generation == Some(self.generation) {
+            return;
+        }
+        self.quality = quality::profile(&self.table_header, &self.items);
+        self.quality_generation = Some(self.generation);
+    }
+
+    pub fn update_box_plot(&mut self) {
+        if self.box_plot.generation == Some(self.generation) {
+            return;
+        }
+        self.box_plot.compute(&self.table_header, &self.items, &self.raw_data);
+        self.box_plot.generation = Some(self.generation);
+    }
+
+    pub fn update_time_series(&mut self) {
+        if self.time_series.generation == Some(self.generation) {
+            return;
+        }
+        self.time_series.compute(&self.table_header, &self.items);
+        self.time_series.generation = Some(self.generation);
+    }
+
+    pub fn update_pivot(&mut self) {
+        if self.pivot.generation == Some(self.generation) {
+            return;
+        }
+        self.pivot.compute(&self.table_header, &self.items);
+        self.pivot.generation = Some(self.generation);
+    }
+
+    /// Shows only the rows matching `filter` in the Data Explorer, or all of
+    /// them again without one.
+    pub fn set_view(&mut self, filter: Option<Filter>) {
+        self.view = filter.map(RowView::new);
+        self.scroll_state = self.scroll_state.content_length(self.items.len().saturating_sub(1) * ITEM_HEIGHT);
+        self.update_view();
+        self.app_state.select(Some(0));
+        self.scroll_state = self.scroll_state.position(0);
+        self.selection_anchor = None;
+        self.select_menu(0);
+        if let Some(view) = &self.view {
+            self.status = view.title.clone();
+        }
+    }
+
+    /// `D` shows the duplicates, or all rows again when they are already shown.
+    pub fn toggle_duplicates(&mut self) {
+        match self.view.as_ref().map(|v| &v.filter) {
+            Some(Filter::Duplicates(_)) => self.set_view(None),
+            _ => self.open_prompt(PromptKind::Duplicates),
+        }
+    }
+
+    /// `O` shows only the rows holding an outlier, or all rows again.
+    pub fn toggle_outliers(&mut self) {
+        match self.view.as_ref().map(|v| &v.filter) {
+            Some(Filter::Outliers) => self.set_view(None),
+            _ => self.set_view(Some(Filter::Outliers)),
+        }
+    }
+
+    /// Switches between IQR and z-score outlier detection.
+    pub fn toggle_outlier_method(&mut self) {
+        self.outliers.toggle_method();
+        if let Some(view) = self.view.as_mut().filter(|v| v.filter == Filter::Outliers) {
+            view.generation = None;
+        }
+        self.status = format!("outliers by {}", self.outliers.method.name());
+    }
+
+    pub fn update_outliers(&mut self) {
+        if self.outliers.generation == Some(self.generation) {
+            return;
+        }
+        self.outliers.compute(&self.table_header, &self.raw_data);
+        self.outliers.generation = Some(self.generation);
+    }
+
+    pub fn update_view(&mut self) {
+        self.update_outliers();
+        let Some(view) = self.view.as_mut() else { return };
+        if view.generation == Some(self.generation) {
+            return;
+        }
+        view.compute(&self.table_header, &self.items, &self.outliers);
+        view.generation = Some(self.generation);
+        self.scroll_state = self.scroll_state.content_length(view.rows.len().saturating_sub(1) * ITEM_HEIGHT);
+    }
+
+    /// Number of rows in the Data Explorer.
+    pub fn row_count(&self) -> usize {
+        match &self.view {
+            Some(view) => view.rows.len(),
+            None => self.items.len(),
+        }
+    }
+
+    /// The record shown in the n-th row of the Data Explorer.
+    pub fn record(&self, row: usize) -> Option<&StringRecord> {
+        self.items.get(self.item_index(row)?)
+    }
+
+    /// Position in `items` of the n-th row of the Data Explorer.
+    pub fn item_index(&self, row: usize) -> Option<usize> {
+        match &self.view {
+            Some(view) => view.rows.get(row).copied(),
+            None => Some(row),
+        }
+    }
+
+    /// Writes the rows of the Data Explorer to `path`. While duplicates are
+    /// shown this writes the deduplicated data instead, `path last` keeping
+    /// the last occurrence of every duplicate rather than the first.
+    fn export_rows(&self, input: &str) -> Result<String, String> {
+        let mut words = input.split_whitespace();
+        let path = words.next().ok_or("usage: <path> [first|last]")?;
+        let rows: Vec<&StringRecord> = match self.view.as_ref().map(|v| &v.filter) {
+            Some(Filter::Duplicates(keys)) => {
+                let keep = match words.next() {
+                    Some(keep) => Keep::from_str(keep).map_err(|_| format!("keep first or last, not {}", keep))?,
+                    None => Keep::First,
+                };
+                duplicates::dedup(&self.items, keys, keep).into_iter().map(|i| &self.items[i]).collect()
+            }
+            _ => (0..self.row_count()).filter_map(|i| self.record(i)).collect(),
+        };
+        view::export(path, &self.table_header, &rows).map_err(|e| format!("could not export: {}", e))?;
+        Ok(format!("exported {} row(s) to {}", rows.len(), path))
+    }
+
+    /// Draws the charts of the Visualization tab into an svg or png image:
+    /// `<path> [<width>x<height>] [line|scatter|box]`, the line and scatter
+    /// charts by default.
+    fn export_chart(&mut self, input: &str) -> Result<String, String> {
+        let mut words = input.split_whitespace();
+        let path = words.next().ok_or("usage: <path.svg|png> [<width>x<height>] [line|scatter|box]")?.to_string();
+        let mut size = chart_export::DEFAULT_SIZE;
+        let mut plots = vec![];
+        for word in words {
+            match word {
+                "line" => plots.push(plot::line_chart(self)),
+                "scatter" => plots.push(plot::scatter_plot(self)),
+                "box" => plots.push(plot::box_plot(self)),
+                _ => {
+                    let (w, h) = word.split_once('x').ok_or(format!("not a size or chart: {}", word))?;
+                    size = match (w.parse(), h.parse()) {
+                        (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+                        _ => return Err(format!("not a size: {}", word)),
+                    };
+                }
+            }
+        }
+        if plots.is_empty() {
+            plots = vec![plot::line_chart(self), plot::scatter_plot(self)];
+        }
+        chart_export::export(&path, &plots, size)?;
+        Ok(format!("exported {}x{} chart to {}", size.0, size.1, path))
+    }
+
+    /// Writes the table of the current tab to a csv file.
+    fn export(&mut self, path: &str) {
+        let result = match self.tab {
+            "Data Explorer" => {
+                self.status = self.export_rows(path).unwrap_or_else(|e| e);
+                return;
+            }
+            "Visualization" => {
+                self.status = self.export_chart(path).unwrap_or_else(|e| e);
+                return;
+            }
+            "Pivot" => self.pivot.export(path),
+            "Extras" => self.sql_console.export(path),
+            _ => {
+                self.status = format!("nothing to export on the {} tab", self.tab);
+                return;
+            }
+        };
+        self.status = match result {
+            Ok(_) => format!("exported to {}", path),
+            Err(e) => format!("could not export: {}", e),
+        };
+    }
+
+    fn run_sql(&mut self, sql: &str) {
+        if !self.is_database() {
+            return self.run_console(sql.trim());
+        }
+        match loaders::sqlite::query(&self.file_path, sql) {
+            Ok(dataset) => {
+                let rows = dataset.records.len();
+                self.set_dataset(dataset);
+                self.tab = TABS[0];
+                self.menu_state.select(Some(0));
+                self.status = format!("query returned {} row(s)", rows);
+            }
+            Err(e) => self.status = format!("query failed: {}", e),
+        }
+    }
+
+    /// Runs a statement in the SQL console of the Extras tab, where the
+    /// loaded file is a table named after it. `.load <path> [name]` adds
+    /// another file as a table and `.export <path>` writes the last result.
+    fn run_console(&mut self, input: &str) {
+        if self.sql_console.generation != Some(self.generation) {
+            let name = sql::table_name(&self.file_path);
+            if let Err(e) = self.sql_console.register(&name, &self.table_header, &self.items, self.numeric_columns.as_deref()) {
+                self.status = format!("could not load {} into the console: {}", name, e);
+                return;
+            }
+            self.sql_console.generation = Some(self.generation);
+        }
+        let mut words = input.split_whitespace();
+        self.status = match words.next() {
+            Some(".load") => match words.next() {
+                Some(path) if !std::path::Path::new(path).is_file() => format!("no such file: {}", path),
+                Some(path) => {
+                    let name = words.next().map(|n| n.to_string()).unwrap_or_else(|| sql::table_name(path));
+                    match get_attrs(path.to_string()) {
+                        Ok(dataset) => match self.sql_console.register(&name, &dataset.headers, &dataset.records, dataset.numeric.as_deref()) {
+                            Ok(_) => format!("loaded {} as table {}", path, name),
+                            Err(e) => format!("could not load {}: {}", path, e),
+                        },
+                        Err(e) => e,
+                    }
+                }
+                None => "usage: .load <path> [name]".to_string(),
+            },
+            Some(".export") => match words.next() {
+                Some(path) => match self.sql_console.export(path) {
+                    Ok(_) => format!("exported {} row(s) to {}", self.sql_console.records.len(), path),
+                    Err(e) => format!("could not export: {}", e),
+                },
+                None => "usage: .export <path>".to_string(),
+            },
+            Some(".tables") => format!("tables: {}", self.sql_console.tables.join(", ")),
+            _ => match self.sql_console.run(input) {
+                Ok(rows) => format!("query returned {} row(s)", rows),
+                Err(e) => format!("query failed: {}", e),
+            },
+        };
+        self.select_menu(TABS.iter().position(|t| *t == "Extras").unwrap_or(0));
+    }
+
+    /// Recomputes the plot data and statistics after `items` changed.
+    pub fn refresh_data(&mut self) {
+        let (grouped_headers, plot_data, raw_data) = parse_records(&self.items, &self.table_header, self.numeric_columns.as_deref());
+        let mut stats_headers = vec![
+            "S/N".to_string(), "Measurement".to_string(),
+        ];
+        for d in raw_data.iter() {
+            stats_headers.push(d.label.clone())
+        }
+        self.grouped_headers = grouped_headers;
+        self.plot_data = plot_data;
+        self.raw_data = raw_data;
+        self.stats_header = stats_headers;
+        self.generation += 1;
+        self.scroll_state = self.scroll_state.content_length(self.items.len().saturating_sub(1) * ITEM_HEIGHT);
+    }
+
+    /// Starts following the file for appended records, showing the last row.
+    pub fn start_follow(&mut self) {
+        if self.source.is_some() || !self.file_info.iter().any(|(k, v)| k == "Format" && v == "CSV") {
+            self.status = "only csv files can be followed".to_string();
+            return;
+        }
+        match Follow::new(&self.file_path) {
+            Ok(follow) => {
+                // appends are read as they come, reloading would start over
+                self.follow = Some(follow);
+                self.watch = None;
+            }
+            Err(e) => {
+                self.status = format!("could not follow {}: {}", self.file_path, e);
+                return;
+            }
+        }
+        self.plot_window();
+        self.select_last_row();
+    }
+
+    /// Appends whatever was written to the followed file since the last call.
+    pub fn follow_file(&mut self) {
+        let Some(follow) = self.follow.as_mut() else { return };
+        match follow.poll() {
+            Ok(records) if !records.is_empty() => self.append_records(records),
+            Ok(_) => {}
+            Err(e) => self.status = format!("could not read {}: {}", self.file_path, e),
+        }
+    }
+
+    /// Adds records without reparsing the whole table: the statistics columns
+    /// grow by the new values and the charts move on to the latest rows. The
+    /// selection stays on the last row unless the user scrolled away from it.
+    fn append_records(&mut self, records: Vec<StringRecord>) {
+        let pinned = self.app_state.selected().is_none_or(|i| i + 1 >= self.row_count());
+        let start = self.items.len();
+        if self.add_rows(records) {
+            self.refresh_data();
+        } else {
+            let columns = self.numeric_column_indices();
+            let new = &self.items[start..];
+            for (data, c) in self.raw_data.iter_mut().zip(columns) {
+                data.data.extend(new.iter().filter_map(|r| r.get(c)?.trim().parse::<f64>().ok()));
+            }
+        }
+        let added = self.items.len().saturating_sub(start);
+        self.generation += 1;
+        self.plot_window();
+        self.update_view();
+        self.scroll_state = self.scroll_state.content_length(self.row_count().saturating_sub(1) * ITEM_HEIGHT);
+        if pinned {
+            self.select_last_row();
+        }
+        if let Some((_, rows)) = self.file_info.iter_mut().find(|(k, _)| k == "Rows") {
+            *rows = self.items.len().to_string();
+        }
+        self.status = format!("+{} row(s), {} in total", added, self.items.len());
+    }
+
+    /// Reloads the file in the background once it was rewritten and swaps
+    /// the new data in when it is read.
+    pub fn watch_file(&mut self) {
+        let Some(watcher) = self.watch.as_mut() else { return };
+        match watcher.take() {
+            Some(Ok(dataset)) => return self.reload(dataset),
+            Some(Err(e)) => self.status = e,
+            None => {}
+        }
+        if watcher.changed() {
+            let read = self.reader();
+            if let Some(watcher) = self.watch.as_mut() {
+                watcher.start(read);
+            }
+            self.status = format!("{} changed, reloading", self.file_path);
+        }
+    }
+
+    /// Reads the same sheet, table or query as the one on screen.
+    fn reader(&self) -> impl FnOnce() -> Result<Dataset, String> + Send + 'static {
+        let path = self.file_path.clone();
+        let info = |key: &str| self.file_info.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+        let (sheet, table, query) = (info("Sheet"), info("Table"), info("Query"));
+        move || match (sheet, table, query) {
+            (Some(sheet), _, _) => loaders::excel::read(&path, Some(&sheet)),
+            (_, Some(table), _) => loaders::sqlite::read_table(&path, &table),
+            (_, _, Some(sql)) => loaders::sqlite::query(&path, &sql).map_err(|e| format!("could not rerun the query: {}", e)),
+            _ => get_attrs(path),
+        }
+    }
+
+    /// Swaps in a reloaded dataset, keeping the tab, the filters, sorts and
+    /// charts, the scroll position and the selected row, found again by its
+    /// key when the file has a column of unique values. Settings that point
+    /// at columns are reset when the columns changed.
+    fn reload(&mut self, mut dataset: Dataset) {
+        let selected = self.app_state.selected().unwrap_or(0);
+        let offset = self.app_state.offset();
+        let key = reload::key_column(&self.table_header, &self.items)
+            .and_then(|c| Some((c, self.record(selected)?.get(c)?.to_string())));
+        // a file read chunk by chunk gets as far as before, so the selected
+        // row can be found again and the steps have rows to run on
+        let loaded = self.base.as_ref().map_or(self.items.len(), |b| b.items.len());
+        if let Err(e) = dataset.load_rows(loaded.max(selected + WINDOW_ROWS)) {
+            return self.status = e;
+        }
+        // the steps run again as far as their columns are still there
+        self.base = None;
+        let steps = std::mem::take(&mut self.steps);
+        if !steps.is_empty() {
+            self.base = Some(Snapshot {
+                headers: dataset.headers.clone(),
+                items: dataset.records.clone(),
+                numeric: dataset.numeric.clone(),
+            });
+        }
+        let mut table = Snapshot {
+            headers: std::mem::take(&mut dataset.headers),
+            items: std::mem::take(&mut dataset.records),
+            numeric: dataset.numeric.take(),
+        };
+        for step in steps {
+            let Ok(mut step) = step.reparse(&table.headers) else { continue };
+            step.pin(&table.headers, &table.items);
+            if table.apply(&step).is_ok() {
+                self.steps.push(step);
+            }
+        }
+        (dataset.headers, dataset.records, dataset.numeric) = (table.headers, table.items, table.numeric);
+        if self.steps.is_empty() {
+            self.base = None;
+        }
+        let same_columns = dataset.headers == self.table_header;
+        self.table_header = dataset.headers;
+        self.items = dataset.records;
+        self.numeric_columns = dataset.numeric;
+        self.file_info = dataset.info;
+        self.source = dataset.source;
+        self.selection_anchor = None;
+        if !same_columns {
+            self.selected_column = 0;
+            self.reset_column_settings();
+        }
+        self.refresh_data();
+        self.update_view();
+
+        let found = key
+            .and_then(|(c, value)| self.items.iter().position(|r| r.get(c) == Some(value.as_str())))
+            .and_then(|i| match &self.view {
+                Some(view) => view.rows.iter().position(|r| *r == i),
+                None => Some(i),
+            });
+        let row = found.unwrap_or(selected).min(self.row_count().saturating_sub(1));
+        self.app_state.select(Some(row));
+        *self.app_state.offset_mut() = offset;
+        self.scroll_state = self.scroll_state.position(row * ITEM_HEIGHT);
+        self.load_window();
+        self.status = match same_columns {
+            true => format!("reloaded {}, {} rows", self.file_path, self.items.len()),
+            false => format!("reloaded {}, the columns changed", self.file_path),
+        };
+    }
+
+    /// Plots only the latest rows, a rolling window over a followed file.
+    fn plot_window(&mut self) {
+        let numeric: Vec<bool> = self.table_header.iter().map(|h| self.raw_data.iter().any(|d| d.label == *h)).collect();
+        let start = self.items.len().saturating_sub(FOLLOW_WINDOW);
+        let (grouped_headers, plot_data, _) = parse_records(&self.items[start..], &self.table_header, Some(&numeric));
+        self.grouped_headers = grouped_headers;
+        self.plot_data = plot_data;
+    }
+
+    fn select_last_row(&mut self) {
+        let last = self.row_count().saturating_sub(1);
+        self.app_state.select(Some(last));
+        self.scroll_state = self.scroll_state.position(last * ITEM_HEIGHT);
+    }
+
+    /// Reads further row groups/record batches until the rows around the
+    /// selected one are in memory. Csv and json files are read upfront and
+    /// have nothing left to load.
+    pub fn load_window(&mut self) {
+        let Some(source) = self.source.as_mut() else { return };
+        let selected = self.app_state.selected().unwrap_or(0);
+        let mut loaded = false;
+        let start = self.items.len();
+        while source.has_more() && selected + WINDOW_ROWS >= self.items.len() {
+            match source.load_next() {
+                Ok(records) => self.items.extend(records),
+                Err(e) => {
+                    self.status = e;
+                    break;
+                }
+            }
+            loaded = true;
+        }
+        if loaded && !self.steps.is_empty() {
+            let records = self.items.split_off(start);
+            self.add_rows(records);
+        }
+        if loaded {
+            self.refresh_data();
         }
     }
     
 
+    /// Marks the summaries of a file that is read chunk by chunk and not
+    /// read to the end yet, as they only cover the rows loaded so far.
+    pub fn partial(&self) -> Option<String> {
+        let source = self.source.as_ref().filter(|s| s.has_more())?;
+        let loaded = self.base.as_ref().map_or(self.items.len(), |b| b.items.len());
+        Some(match self.file_info.iter().find(|(k, _)| k == "Rows") {
+            Some((_, total)) => format!("partial ({} of {} rows)", loaded, total),
+            None => format!("partial ({} rows, {} of {} batches)", loaded, source.loaded, source.chunks()),
+        })
+    }
+
     fn get_menu_items(&self) -> Vec<&'a str> {
-        let menu_items: Vec<&'a str> = vec!["Data Explorer", "Visualization", "Statistics", "Extras"];
-        menu_items
+        TABS.to_vec()
+    }
+
+    fn is_database(&self) -> bool {
+        loaders::sqlite::is_database(&self.file_path)
+    }
+
+    /// Databases list their tables and views below the tabs.
+    fn update_menu(&mut self) {
+        self.menu_items.truncate(TABS.len());
+        if self.is_database() {
+            self.menu_items.extend(self.sheets.iter().map(|t| format!("⛁ {}", t)));
+        }
+        if self.files.len() > 1 {
+            let names = self.files.iter().map(|f| {
+                let name = std::path::Path::new(f).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                match *f == self.file_path {
+                    true => format!("▣ {}", name),
+                    false => format!("□ {}", name),
+                }
+            });
+            self.menu_items.extend(names.collect::<Vec<String>>());
+        }
+        self.longest_menu_item_len = menu_item_len_calculator(&self.menu_items);
+        self.menu_scroll_state = self.menu_scroll_state.content_length((self.menu_items.len() - 1) * ITEM_HEIGHT);
+    }
+
+    fn select_menu(&mut self, i: usize) {
+        self.menu_state.select(Some(i));
+        self.menu_scroll_state = self.menu_scroll_state.position(i * ITEM_HEIGHT);
+        let tabs = self.get_menu_items();
+        match tabs.get(i) {
+            Some(tab) => self.tab = tab,
+            None if i >= self.files_start() => self.file_request = Some(FileRequest::Switch(i - self.files_start())),
+            None => {
+                let table = self.sheets[i - tabs.len()].clone();
+                self.open_sheet(&table);
+                self.tab = tabs[0];
+            }
+        }
+    }
+
+    /// Position of the first open file in the menu, after the tabs and tables.
+    fn files_start(&self) -> usize {
+        match self.files.len() {
+            0 | 1 => self.menu_items.len(),
+            n => self.menu_items.len() - n,
+        }
+    }
+
+    /// Lists the open files below the tabs.
+    pub fn set_files(&mut self, files: Vec<String>) {
+        self.files = files;
+        self.update_menu();
+    }
+
+    /// Highlights the n-th open file in the menu, keeping the current tab.
+    pub fn select_file_entry(&mut self, n: usize) {
+        let i = self.files_start() + n;
+        if i < self.menu_items.len() {
+            self.menu_state.select(Some(i));
+            self.menu_scroll_state = self.menu_scroll_state.position(i * ITEM_HEIGHT);
+        }
     }
 
     pub fn next_menu(&mut self) {
@@ -193,9 +1208,7 @@ impl<'a> App<'a> {
             }
             None => 0,
         };
-        self.menu_state.select(Some(i));
-        self.menu_scroll_state = self.menu_scroll_state.position(i * ITEM_HEIGHT);
-        self.tab = self.get_menu_items()[i];
+        self.select_menu(i);
     }
 
     pub fn previous_menu(&mut self) {
@@ -209,15 +1222,23 @@ impl<'a> App<'a> {
             }
             None => 0,
         };
-        self.menu_state.select(Some(i));
-        self.menu_scroll_state = self.menu_scroll_state.position(i * ITEM_HEIGHT);
-        self.tab = self.get_menu_items()[i]
+        self.select_menu(i);
     }
 
     pub fn next(&mut self) {
+        match self.tab {
+            "Extras" => return self.sql_console.next(),
+            "Group By" => return self.group_by.next(),
+            "Pivot" => return self.pivot.next(),
+            _ => {}
+        }
+        self.update_view();
+        if self.row_count() == 0 {
+            return;
+        }
         let i = match self.app_state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= self.row_count() - 1 {
                     0
                 } else {
                     i + 1
@@ -227,13 +1248,24 @@ impl<'a> App<'a> {
         };
         self.app_state.select(Some(i));
         self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        self.load_window();
     }
 
     pub fn previous(&mut self) {
+        match self.tab {
+            "Extras" => return self.sql_console.previous(),
+            "Group By" => return self.group_by.previous(),
+            "Pivot" => return self.pivot.previous(),
+            _ => {}
+        }
+        self.update_view();
+        if self.row_count() == 0 {
+            return;
+        }
         let i = match self.app_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    self.row_count() - 1
                 } else {
                     i - 1
                 }
@@ -244,6 +1276,124 @@ impl<'a> App<'a> {
         self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
     }
 
+    pub fn next_column(&mut self) {
+        if self.tab == "Visualization" {
+            self.chart_view.pan(Axis::X, 1.0);
+        } else if self.tab == "Group By" {
+            self.group_by.column = (self.group_by.column + 1).min(self.group_by.headers.len().saturating_sub(1));
+        } else if self.tab == "Pivot" {
+            self.pivot.scroll_right();
+        } else if self.tab == "Statistics" {
+            self.correlation.move_cursor(0, 1);
+        } else if self.selected_column + 1 < self.table_header.len() {
+            self.selected_column += 1;
+        }
+    }
+
+    pub fn previous_column(&mut self) {
+        if self.tab == "Visualization" {
+            self.chart_view.pan(Axis::X, -1.0);
+        } else if self.tab == "Group By" {
+            self.group_by.column = self.group_by.column.saturating_sub(1);
+        } else if self.tab == "Pivot" {
+            self.pivot.scroll_left();
+        } else if self.tab == "Statistics" {
+            self.correlation.move_cursor(0, -1);
+        } else {
+            self.selected_column = self.selected_column.saturating_sub(1);
+        }
+    }
+
+    /// Positions in `table_header` of the columns treated as numeric.
+    pub fn numeric_column_indices(&self) -> Vec<usize> {
+        self.raw_data
+            .iter()
+            .filter_map(|d| self.table_header.iter().position(|h| *h == d.label))
+            .collect()
+    }
+
+    /// Adds or removes the column under the cursor from the group by keys.
+    pub fn toggle_group_key(&mut self) {
+        if self.selected_column >= self.table_header.len() {
+            return;
+        }
+        self.group_by.toggle_key(self.selected_column);
+        let keys: Vec<&str> = self.group_by.keys.iter().map(|k| self.table_header[*k].as_str()).collect();
+        self.status = format!("group by: {}", keys.join(", "));
+    }
+
+    /// Regroups when the keys, aggregates or the data changed since the last run.
+    pub fn update_group_by(&mut self) {
+        if self.group_by.generation == Some(self.generation) {
+            return;
+        }
+        let numeric = self.numeric_column_indices();
+        self.group_by.compute(&self.table_header, &self.items, &numeric);
+        self.group_by.generation = Some(self.generation);
+    }
+
+    pub fn toggle_visual(&mut self) {
+        self.selection_anchor = match self.selection_anchor {
+            Some(_) => None,
+            None => Some((self.app_state.selected().unwrap_or(0), self.selected_column)),
+        };
+    }
+
+    pub fn toggle_yank_format(&mut self) {
+        self.yank_format = self.yank_format.toggle();
+        self.status = format!("yank format: {}", self.yank_format.name());
+    }
+
+    /// Returns the selected block as ((first_row, first_col), (last_row, last_col)).
+    /// Without a visual selection this is just the cell under the cursor.
+    pub fn selection(&self) -> ((usize, usize), (usize, usize)) {
+        let cursor = (self.app_state.selected().unwrap_or(0), self.selected_column);
+        let anchor = self.selection_anchor.unwrap_or(cursor);
+        (
+            (anchor.0.min(cursor.0), anchor.1.min(cursor.1)),
+            (anchor.0.max(cursor.0), anchor.1.max(cursor.1)),
+        )
+    }
+
+    pub fn is_selected(&self, row: usize, col: usize) -> bool {
+        let ((r0, c0), (r1, c1)) = self.selection();
+        (r0..=r1).contains(&row) && (c0..=c1).contains(&col)
+    }
+
+    pub fn yank(&mut self) {
+        if self.row_count() == 0 {
+            return;
+        }
+        if self.selection_anchor.is_some() {
+            let ((r0, c0), (r1, c1)) = self.selection();
+            let block: Vec<StringRecord> = (r0..=r1)
+                .filter_map(|r| self.record(r))
+                .map(|r| (c0..=c1).map(|c| r.get(c).unwrap_or("")).collect())
+                .collect();
+            self.selection_anchor = None;
+            self.copy(clipboard::format_records(&block, self.yank_format), format!("{} row(s) x {} column(s)", block.len(), c1 - c0 + 1));
+        } else {
+            let row = self.app_state.selected().unwrap_or(0);
+            let cell = self.record(row).and_then(|r| r.get(self.selected_column)).unwrap_or("").to_string();
+            self.copy(cell, "cell".to_string());
+        }
+    }
+
+    pub fn yank_row(&mut self) {
+        let row = self.app_state.selected().unwrap_or(0);
+        if let Some(record) = self.record(row) {
+            let text = clipboard::format_records(std::slice::from_ref(record), self.yank_format);
+            self.copy(text, format!("row {} as {}", row + 1, self.yank_format.name()));
+        }
+    }
+
+    fn copy(&mut self, text: String, what: String) {
+        self.status = match clipboard::copy(&text) {
+            Ok(_) => format!("copied {} to clipboard", what),
+            Err(e) => format!("could not copy to clipboard: {}", e),
+        };
+    }
+
     pub fn next_color(&mut self) {
         self.color_index = (self.color_index + 1) % PALETTES.len();
     }
@@ -259,10 +1409,40 @@ impl<'a> App<'a> {
 }
 
 
-fn get_attrs(file_path: String) -> (Vec<String>, Vec<StringRecord>) {
-    let file = File::open(file_path).expect("provided file does not exist");
+pub fn get_attrs(file_path: String) -> Result<Dataset, String> {
+    let extension = std::path::Path::new(&file_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "json" | "jsonl" | "ndjson" => {
+            let (headers, records, skipped) = loaders::json::read(&file_path)?;
+            let mut dataset = Dataset::new(&file_path, "JSON", headers, records);
+            if skipped > 0 {
+                dataset.info.push((SKIPPED_LINES.to_string(), skipped.to_string()));
+            }
+            Ok(dataset)
+        }
+        "parquet" | "pq" => ColumnarSource::open(&file_path, Format::Parquet),
+        "arrow" | "feather" | "ipc" => ColumnarSource::open(&file_path, Format::ArrowIpc),
+        "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => loaders::excel::read(&file_path, None),
+        _ if loaders::sqlite::is_database(&file_path) => loaders::sqlite::read(&file_path),
+        _ => {
+            let (headers, records) = read_csv(&file_path)?;
+            Ok(Dataset::new(&file_path, "CSV", headers, records))
+        }
+    }
+}
+
+fn read_csv(file_path: &str) -> Result<(Vec<String>, Vec<StringRecord>), String> {
+    let file = File::open(file_path).map_err(|e| format!("could not open {}: {}", file_path, e))?;
     let mut rdr = ReaderBuilder::new().from_reader(file);
-    let headers: Vec<String> = rdr.headers().expect("could not read headers from csv files").iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    let headers: Vec<String> = rdr
+        .headers()
+        .map_err(|e| format!("could not read the headers of {}: {}", file_path, e))?
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>();
     let headers: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
     // println!("{:?}, {}", headers, headers.len());
     let mut records: Vec<StringRecord> = [].to_vec();
@@ -272,5 +1452,52 @@ fn get_attrs(file_path: String) -> (Vec<String>, Vec<StringRecord>) {
             Err(_) => continue,
         };
     };
-    (headers, records)
-}
\ No newline at end of file
+    Ok((headers, records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(headers: &[&str], rows: &[&[&str]]) -> App<'static> {
+        let headers = headers.iter().map(|h| h.to_string()).collect();
+        let records = rows.iter().map(|r| StringRecord::from(r.to_vec())).collect();
+        App::with_dataset("test.csv".to_string(), Dataset::new("test.csv", "CSV", headers, records))
+    }
+
+    #[test]
+    fn rows_added_after_a_descending_sort_keep_the_order() {
+        let mut app = app(&["n", "id"], &[&["2", "a"], &["", "b"], &["3", "c"], &["1", "d"]]);
+        app.apply_step(Step::transform("n", "sort desc", &app.table_header).unwrap());
+        app.add_rows(vec![
+            StringRecord::from(vec!["2", "e"]),
+            StringRecord::from(vec!["", "f"]),
+            StringRecord::from(vec!["5", "g"]),
+        ]);
+        let ids: Vec<&str> = app.items.iter().map(|r| &r[1]).collect();
+        // ties keep the order they were read in, empty cells go last
+        assert_eq!(ids, vec!["g", "c", "a", "e", "d", "b", "f"]);
+    }
+
+    #[test]
+    fn transforming_without_a_column_says_so() {
+        let mut app = app(&["n"], &[&["1"]]);
+        app.selected_column = 3;
+        app.transform_column("trim");
+        assert!(app.steps.is_empty());
+        assert_eq!(app.status, "no column to transform");
+    }
+
+    #[test]
+    fn a_diff_refuses_steps() {
+        let old = Dataset::new("old.csv", "CSV", vec!["id".to_string()], vec![StringRecord::from(vec!["1"])]);
+        let new = Dataset::new("new.csv", "CSV", vec!["id".to_string()], vec![StringRecord::from(vec!["2"])]);
+        let diff = Diff::compare(&old, &new, &["id".to_string()]).unwrap();
+        let mut app = App::with_dataset("new.csv".to_string(), Dataset::new("new.csv", "CSV diff", diff.headers.clone(), diff.records.clone()));
+        app.diff = Some(diff);
+        app.apply_step(Step::transform("id", "sort desc", &app.table_header).unwrap());
+        assert!(app.steps.is_empty());
+        assert_eq!(app.items.iter().map(|r| r[0].to_string()).collect::<Vec<_>>(), vec!["removed", "added"]);
+        assert!(app.status.starts_with("a diff cannot be transformed"));
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use crate::tui::{models::Data, stats};
+
+// how far outside the quartiles (in interquartile ranges) and from the mean
+// (in standard deviations) a value has to be to count as an outlier
+const IQR_FACTOR: f64 = 1.5;
+const Z_SCORE: f64 = 3.0;
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    Iqr,
+    ZScore,
+}
+
+impl Method {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Iqr => "IQR",
+            Method::ZScore => "z-score",
+        }
+    }
+}
+
+/// Per numeric column, the range outside of which values are outliers.
+pub struct Outliers {
+    pub method: Method,
+    // by position in `App::table_header`
+    pub bounds: BTreeMap<usize, (f64, f64)>,
+    pub generation: Option<usize>,
+}
+
+impl Outliers {
+    pub fn new() -> Outliers {
+        Outliers { method: Method::Iqr, bounds: BTreeMap::new(), generation: None }
+    }
+
+    pub fn compute(&mut self, headers: &[String], columns: &[Data]) {
+        self.bounds = columns
+            .iter()
+            .filter(|c| !c.data.is_empty())
+            .filter_map(|c| Some((headers.iter().position(|h| *h == c.label)?, self.fences(c))))
+            .collect();
+    }
+
+    /// Lower and upper fence of a column under the current method.
+    pub fn fences(&self, column: &Data) -> (f64, f64) {
+        let data = stats::Data { data: column.data.clone() };
+        match self.method {
+            Method::Iqr => tukey_fences(&data),
+            Method::ZScore => {
+                let mean = data.mean();
+                let (_, std) = data.variance_n_std();
+                (mean - Z_SCORE * std, mean + Z_SCORE * std)
+            }
+        }
+    }
+
+    pub fn toggle_method(&mut self) {
+        self.method = match self.method {
+            Method::Iqr => Method::ZScore,
+            Method::ZScore => Method::Iqr,
+        };
+        self.generation = None;
+    }
+
+    pub fn is_outlier(&self, column: usize, value: f64) -> bool {
+        self.bounds.get(&column).is_some_and(|(lo, hi)| value < *lo || value > *hi)
+    }
+
+    /// Whether a table cell holds an outlying number.
+    pub fn is_outlier_cell(&self, column: usize, field: &str) -> bool {
+        field.trim().parse::<f64>().is_ok_and(|v| self.is_outlier(column, v))
+    }
+}
+
+/// Values below the first or above the third quartile by more than 1.5
+/// interquartile ranges are outliers, as in a box plot.
+pub fn tukey_fences(data: &stats::Data) -> (f64, f64) {
+    let (q1, _, q3) = data.percentiles();
+    let iqr = q3 - q1;
+    (q1 - IQR_FACTOR * iqr, q3 + IQR_FACTOR * iqr)
+}
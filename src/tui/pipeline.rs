@@ -0,0 +1,110 @@
+use csv::Writer;
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::fs;
+use std::io;
+use crate::tui::derived::Derived;
+use crate::tui::models;
+use crate::tui::transform::Step;
+
+
+/// Saves the steps as a pipeline, a list of `[[steps]]` tables in TOML or
+/// a json object when the path ends in .json. Each step is one of
+///
+/// - `name` and `derive` (the expression) for a derived column
+/// - `column` and `transform` (the command) for a transform
+/// - `filter` (the expression) for a filter
+pub fn save(path: &str, steps: &[Step]) -> Result<(), String> {
+    let steps: Vec<Value> = steps
+        .iter()
+        .map(|step| match step {
+            Step::Derive(derived) => json!({ "name": derived.name, "derive": derived.expression }),
+            Step::Transform { column, command, .. } => json!({ "column": column, "transform": command }),
+            Step::Filter { expression, .. } => json!({ "filter": expression }),
+        })
+        .collect();
+    let pipeline = json!({ "steps": steps });
+    let text = match is_json(path) {
+        true => serde_json::to_string_pretty(&pipeline).map_err(|e| e.to_string())?,
+        false => toml::to_string(&pipeline).map_err(|e| e.to_string())?,
+    };
+    fs::write(path, text).map_err(|e| format!("could not write {}: {}", path, e))
+}
+
+/// Reads the steps of a pipeline file without parsing them yet, as they
+/// depend on the columns the steps before them leave.
+pub fn load(path: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+    let pipeline: Value = match is_json(path) {
+        true => serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))?,
+        false => toml::from_str(&text).map_err(|e| format!("{}: {}", path, e))?,
+    };
+    let steps = pipeline.get("steps").and_then(|s| s.as_array()).ok_or(format!("{} has no steps", path))?;
+    steps
+        .iter()
+        .map(|step| step.as_object().cloned().ok_or("every step is a table of settings".to_string()))
+        .collect()
+}
+
+/// Parses a step read by `load` against the columns there are at that point.
+pub fn step(settings: &Map<String, Value>, headers: &[String]) -> Result<Step, String> {
+    let field = |key: &str| settings.get(key).and_then(|v| v.as_str());
+    match (field("derive"), field("transform"), field("filter")) {
+        (Some(expression), _, _) => {
+            let name = field("name").unwrap_or(expression);
+            Derived::new(name.to_string(), expression.to_string(), headers).map(Step::Derive)
+        }
+        (_, Some(command), _) => {
+            let column = field("column").ok_or("a transform needs the column it runs on")?;
+            Step::transform(column, command, headers)
+        }
+        (_, _, Some(expression)) => Step::filter(expression, headers),
+        _ => Err("a step needs derive, transform or filter".to_string()),
+    }
+}
+
+fn is_json(path: &str) -> bool {
+    path.to_lowercase().ends_with(".json")
+}
+
+/// `csv-grep run <pipeline> <input> [-o output.csv]` replays a saved
+/// pipeline on a file without the TUI, writing the result as csv to the
+/// output file or stdout. What each step did goes to stderr.
+pub fn main(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "usage: csv-grep run <pipeline.toml|json> <input> [-o output.csv]";
+    let mut paths = vec![];
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(args.next().ok_or(usage)?.clone()),
+            _ => paths.push(arg.clone()),
+        }
+    }
+    let [pipeline, input] = paths.as_slice() else {
+        return Err(usage.into());
+    };
+    let settings = load(pipeline)?;
+    let mut dataset = models::get_attrs(input.clone())?;
+    dataset.load_all()?;
+    let (mut headers, mut records) = (dataset.headers, dataset.records);
+    for (i, settings) in settings.iter().enumerate() {
+        let step = step(settings, &headers).map_err(|e| format!("step {}: {}", i + 1, e))?;
+        let summary = step.apply(&mut headers, &mut records).map_err(|e| format!("step {} ({}): {}", i + 1, step.describe(), e))?;
+        eprintln!("{}", summary);
+    }
+
+    let mut wtr = match &output {
+        Some(path) => Writer::from_writer(Box::new(fs::File::create(path)?) as Box<dyn io::Write>),
+        None => Writer::from_writer(Box::new(io::stdout()) as Box<dyn io::Write>),
+    };
+    wtr.write_record(&headers)?;
+    for record in &records {
+        wtr.write_record(record)?;
+    }
+    wtr.flush()?;
+    if let Some(path) = output {
+        eprintln!("wrote {} row(s) to {}", records.len(), path);
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use csv::{StringRecord, Writer};
+use ratatui::widgets::TableState;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+use crate::tui::group_by::{format_value, Aggregate};
+use crate::tui::stats;
+
+const TOTAL: &str = "Total";
+
+
+/// Which columns make up the pivot, e.g. `state, pick, mean, amout`.
+#[derive(Clone, Debug)]
+pub struct PivotSpec {
+    pub rows: usize,
+    pub columns: usize,
+    pub aggregate: Aggregate,
+    pub value: Option<usize>,
+}
+
+impl PivotSpec {
+    /// Parses `rows, columns[, aggregate, value]`. Without a value column the
+    /// cells count rows.
+    pub fn parse(input: &str, headers: &[String]) -> Result<PivotSpec, String> {
+        let parts: Vec<&str> = input.split(',').map(|p| p.trim()).collect();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| format!("unknown column {}", name))
+        };
+        match parts.as_slice() {
+            [rows, columns] => Ok(PivotSpec { rows: column(rows)?, columns: column(columns)?, aggregate: Aggregate::Count, value: None }),
+            [rows, columns, aggregate, value] => Ok(PivotSpec {
+                rows: column(rows)?,
+                columns: column(columns)?,
+                aggregate: Aggregate::from_str(aggregate).map_err(|_| format!("unknown aggregate {}", aggregate))?,
+                value: Some(column(value)?),
+            }),
+            _ => Err("usage: rows, columns[, aggregate, value]".to_string()),
+        }
+    }
+}
+
+/// A crosstab of one column's values against another's, with totals.
+pub struct Pivot {
+    pub spec: Option<PivotSpec>,
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    // cell values without the totals, used for the heatmap
+    pub cells: Vec<Vec<Option<f64>>>,
+    pub state: TableState,
+    // first value column on screen, pivots can be wider than the terminal
+    pub offset: usize,
+    pub generation: Option<usize>,
+}
+
+impl Pivot {
+    pub fn new() -> Pivot {
+        Pivot {
+            spec: None,
+            title: String::new(),
+            headers: vec![],
+            rows: vec![],
+            cells: vec![],
+            state: TableState::default().with_selected(0),
+            offset: 0,
+            generation: None,
+        }
+    }
+
+    pub fn compute(&mut self, headers: &[String], records: &[StringRecord]) {
+        let Some(spec) = self.spec.clone() else { return };
+        let field = |r: &StringRecord, c: usize| r.get(c).unwrap_or("").to_string();
+        let value = |r: &StringRecord| match spec.value {
+            Some(c) => r.get(c).and_then(|v| v.trim().parse::<f64>().ok()),
+            // counting rows, every row contributes
+            None => Some(1.0),
+        };
+        let aggregate = |values: Vec<f64>| match spec.value {
+            Some(_) => spec.aggregate.compute(&stats::Data { data: values }),
+            None => Some(values.len() as f64),
+        };
+
+        let columns: BTreeSet<String> = records.iter().map(|r| field(r, spec.columns)).collect();
+        let mut grid: BTreeMap<String, BTreeMap<String, Vec<f64>>> = BTreeMap::new();
+        let mut column_values: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        let mut all_values: Vec<f64> = vec![];
+        for record in records {
+            let Some(v) = value(record) else { continue };
+            let (row, column) = (field(record, spec.rows), field(record, spec.columns));
+            grid.entry(row).or_default().entry(column.clone()).or_default().push(v);
+            column_values.entry(column).or_default().push(v);
+            all_values.push(v);
+        }
+
+        self.title = match spec.value {
+            Some(v) => format!("{} of {} by {} and {}", spec.aggregate, headers[v], headers[spec.rows], headers[spec.columns]),
+            None => format!("rows by {} and {}", headers[spec.rows], headers[spec.columns]),
+        };
+        self.headers = vec![headers[spec.rows].clone()];
+        self.headers.extend(columns.iter().cloned());
+        self.headers.push(TOTAL.to_string());
+
+        self.rows.clear();
+        self.cells.clear();
+        for (row, by_column) in &grid {
+            let cells: Vec<Option<f64>> = columns
+                .iter()
+                .map(|c| by_column.get(c).and_then(|v| aggregate(v.clone())))
+                .collect();
+            let total = aggregate(by_column.values().flatten().copied().collect());
+            let mut line = vec![row.clone()];
+            line.extend(cells.iter().map(|c| c.map(format_value).unwrap_or_default()));
+            line.push(total.map(format_value).unwrap_or_default());
+            self.rows.push(line);
+            self.cells.push(cells);
+        }
+        let mut totals = vec![TOTAL.to_string()];
+        totals.extend(columns.iter().map(|c| {
+            column_values.get(c).and_then(|v| aggregate(v.clone())).map(format_value).unwrap_or_default()
+        }));
+        totals.push(aggregate(all_values).map(format_value).unwrap_or_default());
+        self.rows.push(totals);
+        self.offset = self.offset.min(columns.len().saturating_sub(1));
+    }
+
+    /// Lowest and highest cell value, the ends of the heatmap scale.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        let values = self.cells.iter().flatten().flatten();
+        let min = values.clone().copied().reduce(f64::min)?;
+        let max = values.copied().reduce(f64::max)?;
+        Some((min, max))
+    }
+
+    pub fn export(&self, path: &str) -> Result<(), csv::Error> {
+        let mut wtr = Writer::from_path(path)?;
+        wtr.write_record(&self.headers)?;
+        for row in &self.rows {
+            wtr.write_record(row)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.offset = (self.offset + 1).min(self.headers.len().saturating_sub(3));
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    pub fn next(&mut self) {
+        let i = self.state.selected().map(|i| i + 1).unwrap_or(0);
+        self.state.select(Some(i.min(self.rows.len().saturating_sub(1))));
+    }
+
+    pub fn previous(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.state.select(Some(i.saturating_sub(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pivot(spec: &str) -> Pivot {
+        let headers: Vec<String> = ["state", "kind", "amount"].iter().map(|h| h.to_string()).collect();
+        let records: Vec<StringRecord> = [["CA", "a", "10"], ["CA", "b", "20"], ["NY", "a", "30"], ["NY", "a", "50"], ["CA", "a", "x"]]
+            .iter()
+            .map(|r| StringRecord::from(r.to_vec()))
+            .collect();
+        let mut pivot = Pivot::new();
+        pivot.spec = Some(PivotSpec::parse(spec, &headers).unwrap());
+        pivot.compute(&headers, &records);
+        pivot
+    }
+
+    #[test]
+    fn counts_have_row_and_column_totals() {
+        let pivot = pivot("state, kind");
+        assert_eq!(pivot.headers, vec!["state", "a", "b", "Total"]);
+        assert_eq!(pivot.rows, vec![
+            vec!["CA", "2", "1", "3"],
+            vec!["NY", "2", "", "2"],
+            vec!["Total", "4", "1", "5"],
+        ]);
+    }
+
+    #[test]
+    fn totals_aggregate_the_values_not_the_cells() {
+        let pivot = pivot("state, kind, mean, amount");
+        assert_eq!(pivot.title, "mean of amount by state and kind");
+        // the grand total is the mean of all four amounts, not of the means
+        assert_eq!(pivot.rows, vec![
+            vec!["CA", "10", "20", "15"],
+            vec!["NY", "40", "", "40"],
+            vec!["Total", "30", "20", "27.5"],
+        ]);
+        assert_eq!(pivot.bounds(), Some((10.0, 40.0)));
+    }
+}
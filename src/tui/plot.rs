@@ -0,0 +1,197 @@
+use ratatui::{style::Color, symbols::Marker, widgets::GraphType};
+use crate::tui::chart_view::{self, AxisView, ChartView};
+use crate::tui::{models::App, time_series};
+
+// box width in x units, as on the terminal where every box gets one unit
+const BOX_WIDTH: f64 = 0.5;
+const LINE_COLORS: [Color; 14] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+];
+const SCATTER_COLORS: [Color; 9] = [
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Gray,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Green,
+    Color::Yellow,
+    Color::Green,
+];
+
+type Points = Vec<(f64, f64)>;
+
+
+pub struct Series {
+    pub name: Option<String>,
+    pub points: Points,
+    pub color: Color,
+    pub graph_type: GraphType,
+    pub marker: Marker,
+}
+
+/// Everything a chart draws, shared by the terminal and the image export so
+/// both show the same series, bounds and labels.
+pub struct Plot {
+    pub title: String,
+    pub series: Vec<Series>,
+    pub x_title: String,
+    pub y_title: String,
+    pub x_bounds: [f64; 2],
+    pub x_labels: Vec<String>,
+    pub y_bounds: [f64; 2],
+    pub y_labels: Vec<String>,
+}
+
+impl Plot {
+    fn new(title: &str, series: Vec<Series>, x: &AxisView, y: &AxisView) -> Plot {
+        let points = series.iter().flat_map(|s| s.points.iter().copied());
+        let (fit_x, fit_y) = chart_view::fit(points).unwrap_or(((0.0, 1.0), (0.0, 1.0)));
+        let (x_bounds, x_labels) = x.resolve(fit_x);
+        let (y_bounds, y_labels) = y.resolve(fit_y);
+        Plot {
+            title: title.to_string(),
+            series,
+            x_title: "X Axis".to_string(),
+            y_title: "Y Axis".to_string(),
+            x_bounds,
+            x_labels,
+            y_bounds,
+            y_labels,
+        }
+    }
+}
+
+/// The numeric columns plotted pairwise as lines, or the time series once
+/// one is set up.
+pub fn line_chart(app: &mut App) -> Plot {
+    if app.time_series.spec.is_some() {
+        return time_series(app);
+    }
+    let view = &app.chart_view;
+    let series = app.plot_data
+        .iter()
+        .zip(&app.grouped_headers)
+        .enumerate()
+        .map(|(i, (data, h))| Series {
+            name: Some(format!("plot of {} against {}", h.0, h.1)),
+            points: view.transform(data),
+            color: LINE_COLORS[i % LINE_COLORS.len()],
+            graph_type: GraphType::Line,
+            marker: Marker::Braille,
+        })
+        .collect();
+    Plot::new("Line chart", series, &view.x, &view.y)
+}
+
+/// The same column pairs as points, outliers in their own colour on top.
+pub fn scatter_plot(app: &mut App) -> Plot {
+    app.update_outliers();
+    let column = |label: &str| app.table_header.iter().position(|h| h == label);
+    let view = &app.chart_view;
+    let mut series = vec![];
+    let mut outlying = vec![];
+    for (i, (data, h)) in app.plot_data.iter().zip(&app.grouped_headers).enumerate() {
+        let (x, y) = (column(&h.0), column(&h.1));
+        let (inliers, outliers): (Points, Points) = data.iter().partition(|(px, py)| {
+            !(x.is_some_and(|c| app.outliers.is_outlier(c, *px)) || y.is_some_and(|c| app.outliers.is_outlier(c, *py)))
+        });
+        series.push(Series {
+            name: Some(format!("plot of {} against {}", h.0, h.1)),
+            points: view.transform(&inliers),
+            color: SCATTER_COLORS[i % SCATTER_COLORS.len()],
+            graph_type: GraphType::Scatter,
+            marker: Marker::Dot,
+        });
+        if !outliers.is_empty() {
+            outlying.push(Series {
+                name: None,
+                points: view.transform(&outliers),
+                color: app.colors.outlier,
+                graph_type: GraphType::Scatter,
+                marker: Marker::Dot,
+            });
+        }
+    }
+    series.extend(outlying);
+    Plot::new("Scatter chart", series, &view.x, &view.y)
+}
+
+/// The time series, one line per segment so gaps stay empty and lone points
+/// as dots. Dates can be zoomed and panned but not put on a log scale.
+pub fn time_series(app: &mut App) -> Plot {
+    app.update_time_series();
+    let view = &app.chart_view;
+    let x_view = AxisView { manual: None, log: false, ..view.x.clone() };
+    let series = app.time_series
+        .segments
+        .iter()
+        .map(|s| Series {
+            name: None,
+            points: s.iter().filter_map(|(x, y)| Some((*x, view.y.scale(*y)?))).collect(),
+            color: app.colors.selected_style_fg,
+            graph_type: if s.len() > 1 { GraphType::Line } else { GraphType::Scatter },
+            marker: Marker::Braille,
+        })
+        .collect();
+    let mut plot = Plot::new(&app.time_series.title, series, &x_view, &view.y);
+    plot.x_labels = time_series::tick_labels(plot.x_bounds[0], plot.x_bounds[1]);
+    plot.x_title = String::new();
+    plot.y_title = String::new();
+    plot
+}
+
+/// The box plot of the Visualization tab, each box drawn from line segments
+/// at the same places as in the terminal, its label under it.
+pub fn box_plot(app: &mut App) -> Plot {
+    app.update_box_plot();
+    let box_plot = &app.box_plot;
+    let (color, outlier) = (app.colors.selected_style_fg, app.colors.outlier);
+    let line = |points: Points| Series { name: None, points, color, graph_type: GraphType::Line, marker: Marker::Braille };
+    let mut series = vec![];
+    for (i, b) in box_plot.boxes.iter().enumerate() {
+        let x = i as f64 + 0.5;
+        let (left, right) = (x - BOX_WIDTH / 2.0, x + BOX_WIDTH / 2.0);
+        let (cap_left, cap_right) = (x - BOX_WIDTH / 4.0, x + BOX_WIDTH / 4.0);
+        series.push(line(vec![(left, b.q1), (right, b.q1), (right, b.q3), (left, b.q3), (left, b.q1)]));
+        series.push(line(vec![(left, b.median), (right, b.median)]));
+        series.push(line(vec![(x, b.q3), (x, b.upper_whisker)]));
+        series.push(line(vec![(x, b.q1), (x, b.lower_whisker)]));
+        series.push(line(vec![(cap_left, b.upper_whisker), (cap_right, b.upper_whisker)]));
+        series.push(line(vec![(cap_left, b.lower_whisker), (cap_right, b.lower_whisker)]));
+        series.push(Series {
+            name: None,
+            points: b.outliers.iter().map(|v| (x, *v)).collect(),
+            color: outlier,
+            graph_type: GraphType::Scatter,
+            marker: Marker::Dot,
+        });
+    }
+    let (y_bounds, y_labels) = ChartView::new().y.resolve(box_plot.bounds().unwrap_or((0.0, 1.0)));
+    // one tick per box, with an empty one on either side
+    let n = box_plot.boxes.len() as f64;
+    let labels = box_plot.boxes.iter().map(|b| b.label.clone());
+    Plot {
+        title: box_plot.title.clone(),
+        series,
+        x_title: String::new(),
+        y_title: String::new(),
+        x_bounds: [-0.5, n + 0.5],
+        x_labels: std::iter::once(String::new()).chain(labels).chain([String::new()]).collect(),
+        y_bounds,
+        y_labels,
+    }
+}
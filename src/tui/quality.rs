@@ -0,0 +1,208 @@
+use chrono::NaiveDate;
+use csv::{StringRecord, Writer};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use crate::tui::models;
+
+pub const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y", "%Y/%m/%d"];
+pub const HEADERS: [&str; 11] = [
+    "column", "type", "empty", "unparseable", "distinct", "duplicates",
+    "min length", "max length", "whitespace", "rows", "warnings",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    Text,
+}
+
+impl Kind {
+    pub fn of(value: &str) -> Kind {
+        let value = value.trim();
+        if value.parse::<i64>().is_ok() {
+            Kind::Integer
+        } else if value.parse::<f64>().is_ok() {
+            Kind::Float
+        } else if matches!(value.to_lowercase().as_str(), "true" | "false") {
+            Kind::Boolean
+        } else if DATE_FORMATS.iter().any(|f| NaiveDate::parse_from_str(value, f).is_ok()) {
+            Kind::Date
+        } else {
+            Kind::Text
+        }
+    }
+
+    /// Whether a value of `self` can be read as the column type `column`.
+    fn fits(&self, column: Kind) -> bool {
+        *self == column || column == Kind::Text || (*self == Kind::Integer && column == Kind::Float)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Integer => "integer",
+            Kind::Float => "float",
+            Kind::Boolean => "boolean",
+            Kind::Date => "date",
+            Kind::Text => "text",
+        }
+    }
+}
+
+/// Data quality figures of a single column.
+pub struct ColumnProfile {
+    pub name: String,
+    pub kind: Kind,
+    pub empty: usize,
+    pub unparseable: usize,
+    pub distinct: usize,
+    pub duplicates: usize,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub whitespace: usize,
+    pub warnings: Vec<String>,
+    pub rows: usize,
+}
+
+impl ColumnProfile {
+    pub fn to_record(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.kind.name().to_string(),
+            self.empty.to_string(),
+            self.unparseable.to_string(),
+            self.distinct.to_string(),
+            self.duplicates.to_string(),
+            self.min_length.to_string(),
+            self.max_length.to_string(),
+            self.whitespace.to_string(),
+            self.rows.to_string(),
+            self.warnings.join("; "),
+        ]
+    }
+}
+
+pub fn profile(headers: &[String], records: &[StringRecord]) -> Vec<ColumnProfile> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values: Vec<&str> = records.iter().map(|r| r.get(i).unwrap_or("")).collect();
+            profile_column(name, &values)
+        })
+        .collect()
+}
+
+fn profile_column(name: &str, values: &[&str]) -> ColumnProfile {
+    let present: Vec<&str> = values.iter().copied().filter(|v| !v.trim().is_empty()).collect();
+    let mut kinds: HashMap<Kind, usize> = HashMap::new();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in &present {
+        *kinds.entry(Kind::of(value)).or_default() += 1;
+        *counts.entry(value).or_default() += 1;
+    }
+
+    // the most common kind wins, integers mixed with floats are just numbers
+    let count = |k: Kind| kinds.get(&k).copied().unwrap_or(0);
+    let numeric = if count(Kind::Float) > 0 { Kind::Float } else { Kind::Integer };
+    let kind = [
+        (count(Kind::Integer) + count(Kind::Float), numeric),
+        (count(Kind::Boolean), Kind::Boolean),
+        (count(Kind::Date), Kind::Date),
+        (count(Kind::Text), Kind::Text),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .max_by_key(|(n, _)| *n)
+    .map(|(_, k)| k)
+    .unwrap_or(Kind::Text);
+    let unparseable = present.iter().filter(|v| !Kind::of(v).fits(kind)).count();
+
+    let mut warnings = vec![];
+    let mut found: Vec<Kind> = kinds.keys().copied().map(|k| if k == Kind::Integer { Kind::Float } else { k }).collect();
+    found.sort();
+    found.dedup();
+    if found.len() > 1 {
+        let names: Vec<&str> = found.iter().map(|k| k.name()).collect();
+        warnings.push(format!("mixed types: {}", names.join(", ")));
+    }
+    if unparseable > 0 {
+        warnings.push(format!("{} value(s) are not {}", unparseable, kind.name()));
+    }
+    let empty = values.len() - present.len();
+    if empty == values.len() && !values.is_empty() {
+        warnings.push("column is empty".to_string());
+    }
+
+    let lengths = values.iter().map(|v| v.chars().count());
+    ColumnProfile {
+        name: name.to_string(),
+        kind,
+        empty,
+        unparseable,
+        distinct: counts.len(),
+        duplicates: counts.values().filter(|n| **n > 1).map(|n| n - 1).sum(),
+        min_length: lengths.clone().min().unwrap_or(0),
+        max_length: lengths.max().unwrap_or(0),
+        whitespace: values.iter().filter(|v| v.trim() != **v).count(),
+        warnings,
+        rows: values.len(),
+    }
+}
+
+/// Headless `csv-grep profile <file>`: writes the profile as CSV to stdout.
+pub fn print(file_path: String) -> Result<(), Box<dyn Error>> {
+    let mut dataset = models::get_attrs(file_path)?;
+    dataset.load_all()?;
+    let mut wtr = Writer::from_writer(io::stdout());
+    wtr.write_record(HEADERS)?;
+    for column in profile(&dataset.headers, &dataset.records) {
+        wtr.write_record(column.to_record())?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_read_as_the_narrowest_kind() {
+        assert_eq!(Kind::of(" 42 "), Kind::Integer);
+        assert_eq!(Kind::of("-1.5e3"), Kind::Float);
+        assert_eq!(Kind::of("TRUE"), Kind::Boolean);
+        assert_eq!(Kind::of("2024-02-29"), Kind::Date);
+        assert_eq!(Kind::of("29.02.2024"), Kind::Date);
+        assert_eq!(Kind::of("2023-02-29"), Kind::Text);
+        assert_eq!(Kind::of("yes"), Kind::Text);
+    }
+
+    #[test]
+    fn integers_mixed_with_floats_are_floats() {
+        let column = profile_column("n", &["1", "2.5", "3", ""]);
+        assert_eq!(column.kind, Kind::Float);
+        assert_eq!((column.empty, column.unparseable), (1, 0));
+        assert!(column.warnings.is_empty());
+    }
+
+    #[test]
+    fn the_most_common_kind_wins_and_the_rest_is_unparseable() {
+        let column = profile_column("when", &["2024-01-01", "2024-01-02", "soon", "7"]);
+        assert_eq!(column.kind, Kind::Date);
+        assert_eq!(column.unparseable, 2);
+        assert_eq!(column.warnings, vec!["mixed types: float, date, text", "2 value(s) are not date"]);
+    }
+
+    #[test]
+    fn counts_duplicates_whitespace_and_lengths() {
+        let column = profile_column("name", &["Ann", "Ann", " Bob", "Ann", ""]);
+        assert_eq!(column.kind, Kind::Text);
+        assert_eq!((column.distinct, column.duplicates), (2, 2));
+        assert_eq!((column.min_length, column.max_length, column.whitespace), (0, 4, 1));
+        assert_eq!(profile_column("none", &["", " "]).warnings, vec!["column is empty"]);
+    }
+}
@@ -0,0 +1,71 @@
+use csv::StringRecord;
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::SystemTime;
+use crate::tui::loaders::Dataset;
+
+// modification time and size, enough to tell that a file was rewritten
+type Stamp = (SystemTime, u64);
+
+
+/// Watches the open file and reads it again on a background thread once a
+/// job rewrote it, so the table stays usable while a large file loads.
+pub struct Watcher {
+    path: String,
+    // the version of the file on screen
+    loaded: Option<Stamp>,
+    // the version seen on the last look, reloading waits until it holds still
+    seen: Option<Stamp>,
+    pending: Option<Receiver<Result<Dataset, String>>>,
+}
+
+impl Watcher {
+    pub fn new(path: &str) -> Watcher {
+        let loaded = stamp(path);
+        Watcher { path: path.to_string(), loaded, seen: loaded, pending: None }
+    }
+
+    /// Whether the file changed since it was loaded and was left alone since
+    /// the last call, i.e. the job writing it is probably done.
+    pub fn changed(&mut self) -> bool {
+        let current = stamp(&self.path);
+        let settled = current == self.seen;
+        self.seen = current;
+        self.pending.is_none() && current.is_some() && current != self.loaded && settled
+    }
+
+    /// Runs `read` on a background thread, `take` hands over its result.
+    pub fn start<F: FnOnce() -> Result<Dataset, String> + Send + 'static>(&mut self, read: F) {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(read());
+        });
+        self.loaded = self.seen;
+        self.pending = Some(rx);
+    }
+
+    /// The reloaded dataset once the background read is done.
+    pub fn take(&mut self) -> Option<Result<Dataset, String>> {
+        let result = match self.pending.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(format!("could not reload {}", self.path)),
+        };
+        self.pending = None;
+        Some(result)
+    }
+}
+
+fn stamp(path: &str) -> Option<Stamp> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// First column whose values are all present and distinct, used to find the
+/// selected row again after a reload.
+pub fn key_column(headers: &[String], records: &[StringRecord]) -> Option<usize> {
+    (0..headers.len()).find(|c| {
+        let mut values = HashSet::new();
+        records.iter().all(|r| matches!(r.get(*c), Some(v) if !v.is_empty() && values.insert(v)))
+    })
+}
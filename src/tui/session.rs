@@ -0,0 +1,168 @@
+use std::path::Path;
+use crate::tui::loaders::Dataset;
+use crate::tui::models::App;
+
+
+/// What an `App` asks of the session it belongs to, from the menu or the
+/// open file prompt.
+pub enum FileRequest {
+    Open(String),
+    Switch(usize),
+    // a table made up in the app, e.g. a join, under a name of its own
+    Add(String, Dataset),
+}
+
+/// Every file opened in this run, each with its own `App` (tab, selection,
+/// filters, charts), one of them on screen.
+pub struct Session<'a> {
+    pub apps: Vec<App<'a>>,
+    pub active: usize,
+    follow: bool,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(file_paths: Vec<String>, follow: bool) -> Result<Session<'a>, String> {
+        let mut session = Session { apps: vec![], active: 0, follow };
+        for path in file_paths {
+            let app = session.load(path)?;
+            session.apps.push(app);
+        }
+        session.sync_menus();
+        Ok(session)
+    }
+
+    /// A session around an app that was set up elsewhere, e.g. a diff.
+    pub fn from_app(app: App<'a>) -> Session<'a> {
+        Session { apps: vec![app], active: 0, follow: false }
+    }
+
+    fn load(&self, path: String) -> Result<App<'a>, String> {
+        let mut app = App::new(path)?;
+        if self.follow {
+            app.start_follow();
+        }
+        Ok(app)
+    }
+
+    pub fn app(&mut self) -> &mut App<'a> {
+        &mut self.apps[self.active]
+    }
+
+    /// Opens `path` in a new tab, or switches to it when it is already open.
+    pub fn open(&mut self, path: &str) {
+        if let Some(i) = self.apps.iter().position(|a| a.file_path == path) {
+            return self.switch(i);
+        }
+        if !Path::new(path).is_file() {
+            self.app().status = format!("no such file: {}", path);
+            return;
+        }
+        let app = match self.load(path.to_string()) {
+            Ok(app) => app,
+            Err(e) => return self.app().status = e,
+        };
+        self.apps.push(app);
+        self.sync_menus();
+        self.switch(self.apps.len() - 1);
+        self.app().status = format!("opened {}", path);
+    }
+
+    /// Opens a dataset that was not read from a file, handing the status of
+    /// the app that made it over to the new one.
+    pub fn add(&mut self, name: String, dataset: Dataset) {
+        let mut app = App::with_dataset(name, dataset);
+        app.watch = None;
+        app.status = std::mem::take(&mut self.app().status);
+        self.apps.push(app);
+        self.sync_menus();
+        self.switch(self.apps.len() - 1);
+    }
+
+    /// Puts the i-th file on screen as it was left, with its own entry
+    /// selected in the menu.
+    pub fn switch(&mut self, i: usize) {
+        if i >= self.apps.len() {
+            return;
+        }
+        self.active = i;
+        self.app().select_file_entry(i);
+    }
+
+    /// Acts on what the app on screen asked for during the last key press.
+    pub fn handle_request(&mut self) {
+        match self.app().file_request.take() {
+            Some(FileRequest::Open(path)) => self.open(&path),
+            Some(FileRequest::Switch(i)) => self.switch(i),
+            Some(FileRequest::Add(name, dataset)) => self.add(name, dataset),
+            None => {}
+        }
+    }
+
+    /// Follows and watches every open file, not only the one on screen.
+    pub fn poll(&mut self) {
+        for app in self.apps.iter_mut() {
+            app.follow_file();
+            app.watch_file();
+        }
+    }
+
+    pub fn is_polling(&self) -> bool {
+        self.apps.iter().any(|a| a.follow.is_some() || a.watch.is_some())
+    }
+
+    fn sync_menus(&mut self) {
+        let paths: Vec<String> = self.apps.iter().map(|a| a.file_path.clone()).collect();
+        for app in self.apps.iter_mut() {
+            app.set_files(paths.clone());
+        }
+    }
+}
+
+/// Expands glob patterns among the command line arguments, which matters
+/// where the shell does not, e.g. for quoted patterns. Anything that is not
+/// a pattern or matches nothing is kept as it is.
+pub fn expand(args: &[String]) -> Vec<String> {
+    let mut paths = vec![];
+    for arg in args {
+        let matches: Vec<String> = match glob::glob(arg) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|p| p.to_string_lossy().to_string()).collect(),
+            Err(_) => vec![],
+        };
+        match matches.is_empty() {
+            true => paths.push(arg.clone()),
+            false => paths.extend(matches),
+        }
+    }
+    paths
+}
+
+/// Completes the path typed so far as far as it is unambiguous. Returns the
+/// new input and the names it could still become.
+pub fn complete(input: &str) -> (String, Vec<String>) {
+    let (dir, prefix) = match input.rfind('/') {
+        Some(i) => (&input[..=i], &input[i + 1..]),
+        None => ("", input),
+    };
+    let Ok(entries) = std::fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return (input.to_string(), vec![]);
+    };
+    let mut candidates: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            (name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+                .then(|| if is_dir { format!("{}/", name) } else { name })
+        })
+        .collect();
+    candidates.sort();
+    let Some(first) = candidates.first() else { return (input.to_string(), vec![]) };
+    let common = candidates.iter().fold(first.clone(), |common, c| {
+        common.chars().zip(c.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect()
+    });
+    let completed = format!("{}{}", dir, common);
+    match candidates.len() {
+        1 => (completed, vec![]),
+        _ => (completed, candidates),
+    }
+}
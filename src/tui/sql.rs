@@ -0,0 +1,134 @@
+use csv::{StringRecord, Writer};
+use ratatui::widgets::TableState;
+use rusqlite::{types::Value, Connection};
+use crate::tui::loaders::sqlite;
+
+
+/// An in-memory sqlite database holding the loaded file (and any file
+/// added with `.load`) as tables, backing the SQL console of the Extras tab.
+pub struct SqlConsole {
+    conn: Connection,
+    pub tables: Vec<String>,
+    pub query: String,
+    pub headers: Vec<String>,
+    pub records: Vec<StringRecord>,
+    pub numeric: Vec<bool>,
+    pub state: TableState,
+    pub error: Option<String>,
+    // `App::generation` the main table was built from
+    pub generation: Option<usize>,
+}
+
+impl SqlConsole {
+    pub fn new() -> SqlConsole {
+        SqlConsole {
+            conn: Connection::open_in_memory().expect("could not create in-memory database"),
+            tables: vec![],
+            query: String::new(),
+            headers: vec![],
+            records: vec![],
+            numeric: vec![],
+            state: TableState::default(),
+            error: None,
+            generation: None,
+        }
+    }
+
+    /// (Re)creates `name` from records. Numeric columns get NUMERIC affinity so
+    /// comparisons and aggregates behave numerically, empty cells become NULL.
+    pub fn register(&mut self, name: &str, headers: &[String], records: &[StringRecord], numeric: Option<&[bool]>) -> rusqlite::Result<()> {
+        let numeric: Vec<bool> = match numeric {
+            Some(flags) => flags.to_vec(),
+            None => (0..headers.len())
+                .map(|i| {
+                    let mut values = records.iter().filter_map(|r| r.get(i)).filter(|v| !v.trim().is_empty()).peekable();
+                    values.peek().is_some() && values.all(|v| v.trim().parse::<f64>().is_ok())
+                })
+                .collect(),
+        };
+        let columns: Vec<String> = headers
+            .iter()
+            .zip(&numeric)
+            .map(|(h, n)| format!("{} {}", quote(h), if *n { "NUMERIC" } else { "TEXT" }))
+            .collect();
+
+        let tx = self.conn.transaction()?;
+        tx.execute_batch(&format!("DROP TABLE IF EXISTS {0}; CREATE TABLE {0} ({1});", quote(name), columns.join(", ")))?;
+        {
+            let placeholders = vec!["?"; headers.len()].join(", ");
+            let mut stmt = tx.prepare(&format!("INSERT INTO {} VALUES ({})", quote(name), placeholders))?;
+            for record in records {
+                let values = (0..headers.len()).map(|i| {
+                    let field = record.get(i).unwrap_or("");
+                    if field.is_empty() {
+                        return Value::Null;
+                    }
+                    match (numeric[i], field.trim().parse::<i64>(), field.trim().parse::<f64>()) {
+                        (true, Ok(v), _) => Value::Integer(v),
+                        (true, _, Ok(v)) => Value::Real(v),
+                        _ => Value::Text(field.to_string()),
+                    }
+                });
+                stmt.execute(rusqlite::params_from_iter(values))?;
+            }
+        }
+        tx.commit()?;
+        if !self.tables.iter().any(|t| t == name) {
+            self.tables.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Runs a statement and keeps its result set for display and export.
+    pub fn run(&mut self, sql: &str) -> Result<usize, String> {
+        self.query = sql.to_string();
+        match sqlite::run(&self.conn, sql) {
+            Ok((headers, records, numeric)) => {
+                self.headers = headers;
+                self.records = records;
+                self.numeric = numeric;
+                self.error = None;
+                self.state.select(Some(0));
+                Ok(self.records.len())
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                Err(e.to_string())
+            }
+        }
+    }
+
+    pub fn export(&self, path: &str) -> Result<(), csv::Error> {
+        let mut wtr = Writer::from_path(path)?;
+        wtr.write_record(&self.headers)?;
+        for record in &self.records {
+            wtr.write_record(record)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    pub fn next(&mut self) {
+        let i = self.state.selected().map(|i| i + 1).unwrap_or(0);
+        self.state.select(Some(i.min(self.records.len().saturating_sub(1))));
+    }
+
+    pub fn previous(&mut self) {
+        let i = self.state.selected().unwrap_or(0);
+        self.state.select(Some(i.saturating_sub(1)));
+    }
+}
+
+/// Table name for a file: its stem with anything but letters, digits and
+/// underscores replaced, e.g. `daily-export.csv` becomes `daily_export`.
+pub fn table_name(file_path: &str) -> String {
+    let stem = std::path::Path::new(file_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "data".to_string());
+    stem.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
@@ -4,7 +4,41 @@ pub struct Data {
 
 impl Data {
     pub fn mean(&self) -> f64{
-        return self.data.iter().sum::<f64>() as f64 / self.data.len() as f64;
+        self.data.iter().sum::<f64>() / self.data.len() as f64
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.data.iter().sum::<f64>()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.data.iter().copied().min_by(|a, b| a.total_cmp(b))
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.data.iter().copied().max_by(|a, b| a.total_cmp(b))
+    }
+
+    /// The middle value, or the mean of the two middle values for an even
+    /// count.
+    pub fn median(&self) -> Option<f64> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut sorted_data = self.data.clone();
+        sorted_data.sort_by(|a, b| a.total_cmp(b));
+        let middle = sorted_data.len() / 2;
+        match sorted_data.len() % 2 {
+            0 => Some((sorted_data[middle - 1] + sorted_data[middle]) / 2.0),
+            _ => Some(sorted_data[middle]),
+        }
+    }
+
+    pub fn distinct_count(&self) -> usize {
+        let mut sorted_data = self.data.clone();
+        sorted_data.sort_by(|a, b| a.total_cmp(b));
+        sorted_data.dedup();
+        sorted_data.len()
     }
 
     pub fn range(&self) -> Option<f64> {
@@ -22,7 +56,7 @@ impl Data {
 
     pub fn variance_n_std(&self) -> (f64, f64) {
         let mean = self.mean(); // Convert mean to integer for simplicity
-        let variance: f64 = self.data.iter().map(|&x| ((x - mean) as f64).powi(2)).sum::<f64>() / self.data.len() as f64;
+        let variance: f64 = self.data.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / self.data.len() as f64;
         let standard_deviation = variance.sqrt();
         (variance, standard_deviation)
     }
@@ -36,9 +70,12 @@ impl Data {
         (percentile_25, percentile_50, percentile_75)
     }
 
-    fn percentile(&self, data: &Vec<f64>, p: usize) -> f64 {
+    fn percentile(&self, data: &[f64], p: usize) -> f64 {
+        if data.is_empty() {
+            return f64::NAN;
+        }
         let index = (p as f64 / 100.0 * (data.len() - 1) as f64).round() as usize;
-        data[index] as f64
+        data[index]
     }
     
     pub fn skewness(&self) -> f64 {
@@ -46,7 +83,7 @@ impl Data {
         let (_, std) = self.variance_n_std();
         let skewness: f64 = self.data
             .iter()
-            .map(|&x| ((x - self.mean()) as f64 / std).powi(3))
+            .map(|&x| ((x - self.mean()) / std).powi(3))
             .sum::<f64>()
             / (n * (n - 1.0) * (n - 2.0))
             * n.sqrt();
@@ -58,10 +95,67 @@ impl Data {
         let (_, std) = self.variance_n_std();
         let kurtosis: f64 = self.data
             .iter()
-            .map(|&x| ((x - self.mean()) as f64 / std).powi(4))
+            .map(|&x| ((x - self.mean()) / std).powi(4))
             .sum::<f64>()
             / ((n - 1.0) * (n - 2.0) * (n - 3.0))
             * (n * (n + 1.0) / ((n - 1.0) * (n - 2.0)).powi(2));
         kurtosis - 3.0
     }
 }
+
+/// Pearson correlation of two equally long series.
+pub fn pearson(x: &[f64], y: &[f64]) -> Option<f64> {
+    if x.len() != y.len() || x.len() < 2 {
+        return None;
+    }
+    let (mx, my) = (Data { data: x.to_vec() }.mean(), Data { data: y.to_vec() }.mean());
+    let cov: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+    let sx: f64 = x.iter().map(|a| (a - mx).powi(2)).sum::<f64>().sqrt();
+    let sy: f64 = y.iter().map(|b| (b - my).powi(2)).sum::<f64>().sqrt();
+    if sx == 0.0 || sy == 0.0 {
+        return None;
+    }
+    Some(cov / (sx * sy))
+}
+
+/// Spearman rank correlation, ties get the average of their ranks.
+pub fn spearman(x: &[f64], y: &[f64]) -> Option<f64> {
+    pearson(&ranks(x), &ranks(y))
+}
+
+fn ranks(data: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..data.len()).collect();
+    order.sort_by(|a, b| data[*a].total_cmp(&data[*b]));
+    let mut ranks = vec![0.0; data.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && data[order[j + 1]] == data[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for k in i..=j {
+            ranks[order[k]] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn median(values: &[f64]) -> Option<f64> {
+        Data { data: values.to_vec() }.median()
+    }
+
+    #[test]
+    fn median_averages_the_middle_values_of_an_even_count() {
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), Some(2.5));
+        assert_eq!(median(&[10.0, 1.0]), Some(5.5));
+        assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(&[-1.0]), Some(-1.0));
+        assert_eq!(median(&[]), None);
+    }
+}
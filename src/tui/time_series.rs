@@ -0,0 +1,248 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime};
+use csv::StringRecord;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use crate::tui::group_by::Aggregate;
+use crate::tui::{quality, stats};
+
+pub const DATETIME_FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%m/%d/%Y %H:%M", "%d.%m.%Y %H:%M"];
+const TICKS: usize = 5;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Display, EnumString)]
+pub enum Resample {
+    #[strum(to_string = "daily")]
+    Daily,
+    #[strum(to_string = "weekly")]
+    Weekly,
+    #[strum(to_string = "monthly")]
+    Monthly,
+    #[strum(to_string = "yearly")]
+    Yearly,
+}
+
+impl Resample {
+    /// First moment of the bucket `t` falls in.
+    fn bucket(&self, t: NaiveDateTime) -> NaiveDate {
+        let date = t.date();
+        match self {
+            Resample::Daily => date,
+            Resample::Weekly => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Resample::Monthly => date.with_day(1).unwrap_or(date),
+            Resample::Yearly => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+        }
+    }
+
+    fn next(&self, bucket: NaiveDate) -> NaiveDate {
+        match self {
+            Resample::Daily => bucket + Duration::days(1),
+            Resample::Weekly => bucket + Duration::days(7),
+            Resample::Monthly => bucket.checked_add_months(chrono::Months::new(1)).unwrap_or(bucket),
+            Resample::Yearly => bucket.checked_add_months(chrono::Months::new(12)).unwrap_or(bucket),
+        }
+    }
+}
+
+/// Which columns to plot over time and how to bucket them.
+#[derive(Clone, Debug)]
+pub struct TimeSeriesSpec {
+    pub date: usize,
+    pub value: usize,
+    pub resample: Option<(Resample, Aggregate)>,
+    // strftime format of the date column, guessed when not given
+    pub format: Option<String>,
+}
+
+impl TimeSeriesSpec {
+    /// Parses `date, value[, resample[, aggregate]][, format]`, e.g.
+    /// `date, amout, monthly, sum` or `date, amout, %m/%d/%Y`.
+    pub fn parse(input: &str, headers: &[String]) -> Result<TimeSeriesSpec, String> {
+        let mut parts: Vec<&str> = input.split(',').map(|p| p.trim()).collect();
+        let format = match parts.last() {
+            Some(p) if p.contains('%') => parts.pop().map(|f| f.to_string()),
+            _ => None,
+        };
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| format!("unknown column {}", name))
+        };
+        let resample = |name: &str| Resample::from_str(name).map_err(|_| format!("resample daily, weekly, monthly or yearly, not {}", name));
+        let aggregate = |name: &str| Aggregate::from_str(name).map_err(|_| format!("unknown aggregate {}", name));
+        let (date, value, resample) = match parts.as_slice() {
+            [date, value] => (column(date)?, column(value)?, None),
+            [date, value, r] => (column(date)?, column(value)?, Some((resample(r)?, Aggregate::Mean))),
+            [date, value, r, a] => (column(date)?, column(value)?, Some((resample(r)?, aggregate(a)?))),
+            _ => return Err("usage: date, value[, daily|weekly|monthly|yearly[, aggregate]][, format]".to_string()),
+        };
+        Ok(TimeSeriesSpec { date, value, resample, format })
+    }
+}
+
+/// A value column over a date column, as line segments broken at gaps.
+pub struct TimeSeries {
+    pub spec: Option<TimeSeriesSpec>,
+    pub title: String,
+    // x is days since the unix epoch
+    pub segments: Vec<Vec<(f64, f64)>>,
+    pub unparsed: usize,
+    pub generation: Option<usize>,
+}
+
+impl TimeSeries {
+    pub fn new() -> TimeSeries {
+        TimeSeries { spec: None, title: String::new(), segments: vec![], unparsed: 0, generation: None }
+    }
+
+    pub fn compute(&mut self, headers: &[String], records: &[StringRecord]) {
+        let Some(spec) = self.spec.clone() else { return };
+        let format = spec.format.clone().or_else(|| guess_format(records.iter().filter_map(|r| r.get(spec.date))));
+        let mut points: Vec<(NaiveDateTime, Option<f64>)> = vec![];
+        self.unparsed = 0;
+        for record in records {
+            let Some(t) = format.as_deref().and_then(|f| parse(record.get(spec.date).unwrap_or(""), f)) else {
+                self.unparsed += 1;
+                continue;
+            };
+            points.push((t, record.get(spec.value).and_then(|v| v.trim().parse::<f64>().ok())));
+        }
+        points.sort_by_key(|(t, _)| *t);
+
+        // a missing value, or an empty bucket when resampling, breaks the line
+        let series: Vec<(NaiveDateTime, Option<f64>)> = match spec.resample {
+            None => points,
+            Some((resample, aggregate)) => {
+                let mut buckets: BTreeMap<NaiveDate, Vec<f64>> = BTreeMap::new();
+                for (t, v) in points {
+                    let values = buckets.entry(resample.bucket(t)).or_default();
+                    values.extend(v);
+                }
+                let mut series = vec![];
+                let mut expected: Option<NaiveDate> = None;
+                for (bucket, values) in buckets {
+                    if expected.is_some_and(|e| e < bucket) {
+                        series.push((bucket.and_hms_opt(0, 0, 0).unwrap_or_default(), None));
+                    }
+                    let value = aggregate.compute(&stats::Data { data: values.clone() }).filter(|_| !values.is_empty());
+                    series.push((bucket.and_hms_opt(0, 0, 0).unwrap_or_default(), value));
+                    expected = Some(resample.next(bucket));
+                }
+                series
+            }
+        };
+        self.segments = vec![vec![]];
+        for (t, v) in series {
+            match v {
+                Some(v) => self.segments.last_mut().unwrap().push((days(t), v)),
+                None if self.segments.last().is_some_and(|s| !s.is_empty()) => self.segments.push(vec![]),
+                None => {}
+            }
+        }
+        self.segments.retain(|s| !s.is_empty());
+
+        self.title = match spec.resample {
+            Some((resample, aggregate)) => format!("{} {} of {} by {}", resample, aggregate, headers[spec.value], headers[spec.date]),
+            None => format!("{} by {}", headers[spec.value], headers[spec.date]),
+        };
+        if self.unparsed > 0 {
+            self.title = format!("{} ({} rows without a date)", self.title, self.unparsed);
+        }
+    }
+}
+
+/// The first known format every non empty value parses with.
+pub fn guess_format<'a>(values: impl Iterator<Item = &'a str> + Clone) -> Option<String> {
+    let mut values = values.filter(|v| !v.trim().is_empty()).peekable();
+    values.peek()?;
+    let formats = quality::DATE_FORMATS.iter().chain(DATETIME_FORMATS.iter());
+    formats.clone().find(|f| values.clone().all(|v| parse(v, f).is_some())).map(|f| f.to_string())
+}
+
+pub fn parse(value: &str, format: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, format)
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, format).ok()?.and_hms_opt(0, 0, 0))
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|t| t.naive_utc()))
+}
+
+fn days(t: NaiveDateTime) -> f64 {
+    t.and_utc().timestamp() as f64 / 86400.0
+}
+
+fn from_days(x: f64) -> NaiveDateTime {
+    DateTime::from_timestamp((x * 86400.0) as i64, 0).unwrap_or_default().naive_utc()
+}
+
+/// Evenly spaced labels across `[x0, x1]`, as years, months or days
+/// depending on how much time the axis spans.
+pub fn tick_labels(x0: f64, x1: f64) -> Vec<String> {
+    let span = x1 - x0;
+    let format = if span > 3.0 * 365.0 {
+        "%Y"
+    } else if span > 90.0 {
+        "%b %Y"
+    } else if span > 2.0 {
+        "%b %d"
+    } else {
+        "%m-%d %H:%M"
+    };
+    (0..TICKS)
+        .map(|i| from_days(x0 + span * i as f64 / (TICKS - 1) as f64).format(format).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(text: &str) -> f64 {
+        days(NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap().and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn series(spec: &str, rows: &[[&str; 2]]) -> TimeSeries {
+        let headers = vec!["date".to_string(), "amount".to_string()];
+        let records: Vec<StringRecord> = rows.iter().map(|r| StringRecord::from(r.to_vec())).collect();
+        let mut series = TimeSeries::new();
+        series.spec = Some(TimeSeriesSpec::parse(spec, &headers).unwrap());
+        series.compute(&headers, &records);
+        series
+    }
+
+    #[test]
+    fn the_format_is_the_first_every_date_parses_with() {
+        let guess = |values: &[&str]| guess_format(values.iter().copied());
+        assert_eq!(guess(&["01/31/2024", "", "02/01/2024"]), Some("%m/%d/%Y".to_string()));
+        assert_eq!(guess(&["2024-01-31 10:00:00"]), Some("%Y-%m-%d %H:%M:%S".to_string()));
+        assert_eq!(guess(&["31.01.2024 10:30"]), Some("%d.%m.%Y %H:%M".to_string()));
+        // ambiguous days and months are read the american way
+        assert_eq!(guess(&["01/02/2024"]), Some("%m/%d/%Y".to_string()));
+        assert_eq!(guess(&["2024-01-31", "31.01.2024"]), None);
+        assert_eq!(guess(&["", " "]), None);
+    }
+
+    #[test]
+    fn resampling_aggregates_buckets_and_breaks_at_empty_ones() {
+        let rows = [["2024-01-05", "1"], ["2024-01-20", "2"], ["2024-03-01", "4"], ["later", "8"]];
+        let series = series("date, amount, monthly, sum, %Y-%m-%d", &rows);
+        assert_eq!(series.segments, vec![vec![(day("2024-01-01"), 3.0)], vec![(day("2024-03-01"), 4.0)]]);
+        assert_eq!(series.unparsed, 1);
+        assert_eq!(series.title, "monthly sum of amount by date (1 rows without a date)");
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let rows = [["2024-01-03", "2"], ["2024-01-07", "4"], ["2024-01-08", "5"]];
+        let series = series("date, amount, weekly", &rows);
+        assert_eq!(series.segments, vec![vec![(day("2024-01-01"), 3.0), (day("2024-01-08"), 5.0)]]);
+    }
+
+    #[test]
+    fn without_resampling_points_are_sorted_and_missing_values_break_the_line() {
+        let rows = [["2024-01-03", "2"], ["2024-01-01", "1"], ["2024-01-02", ""]];
+        let series = series("date, amount", &rows);
+        assert_eq!(series.segments, vec![vec![(day("2024-01-01"), 1.0)], vec![(day("2024-01-03"), 2.0)]]);
+    }
+}
@@ -0,0 +1,614 @@
+use csv::StringRecord;
+use regex::Regex;
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use crate::tui::derived::{self, Derived};
+use crate::tui::expr::{self, Expr, Value};
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Display, EnumString)]
+pub enum Cast {
+    #[strum(to_string = "integer")]
+    Integer,
+    #[strum(to_string = "float")]
+    Float,
+    #[strum(to_string = "text")]
+    Text,
+    #[strum(to_string = "date")]
+    Date,
+    #[strum(to_string = "boolean")]
+    Boolean,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Case {
+    Upper,
+    Lower,
+    Title,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fill {
+    Value(String),
+    Forward,
+    Backward,
+    Mean,
+}
+
+/// What a command typed at the transform prompt does to a column.
+#[derive(Clone, Debug)]
+pub enum Transform {
+    Cast(Cast),
+    Trim,
+    Case(Case),
+    Replace(Regex, String),
+    // into `column/1`, `column/2`, ... in its place, as many columns as the
+    // step was pinned to, the most parts of any value when it is not
+    Split(String, Option<usize>),
+    // other columns and the separator, into the transformed column
+    Merge(Vec<String>, String),
+    Fill(Fill),
+    Round(usize),
+    // the rows by the column, descending when true; empty cells go last
+    Sort(bool),
+}
+
+pub const USAGE: &str =
+    "cast integer|float|text|date|boolean, trim, upper, lower, title, replace <regex> => <text>, split <delimiter>, merge <columns> [with <separator>], fill <value>|forward|backward|mean, round [digits], sort [asc|desc]";
+
+impl Transform {
+    pub fn parse(command: &str, headers: &[String]) -> Result<Transform, String> {
+        let command = command.trim();
+        let (verb, rest) = command.split_once(' ').unwrap_or((command, ""));
+        let rest = rest.trim();
+        match verb {
+            "cast" => Cast::from_str(rest).map(Transform::Cast).map_err(|_| format!("cast to integer, float, text, date or boolean, not {}", rest)),
+            "trim" => Ok(Transform::Trim),
+            "upper" => Ok(Transform::Case(Case::Upper)),
+            "lower" => Ok(Transform::Case(Case::Lower)),
+            "title" => Ok(Transform::Case(Case::Title)),
+            "replace" => {
+                let (pattern, replacement) = rest.split_once("=>").ok_or("usage: replace <regex> => <text>")?;
+                let regex = Regex::new(pattern.trim()).map_err(|e| e.to_string())?;
+                Ok(Transform::Replace(regex, unquote(replacement.trim())))
+            }
+            "split" if !rest.is_empty() => Ok(Transform::Split(unquote(rest), None)),
+            "merge" => {
+                let (columns, separator) = match rest.split_once(" with ") {
+                    Some((columns, separator)) => (columns, unquote(separator.trim())),
+                    None => (rest, " ".to_string()),
+                };
+                let columns: Vec<String> = columns.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+                if columns.is_empty() {
+                    return Err("usage: merge <column>[, column] [with <separator>]".to_string());
+                }
+                if let Some(unknown) = columns.iter().find(|c| !headers.contains(c)) {
+                    return Err(format!("unknown column {}", unknown));
+                }
+                Ok(Transform::Merge(columns, separator))
+            }
+            "fill" => Ok(Transform::Fill(match rest {
+                "" => return Err("usage: fill <value>|forward|backward|mean".to_string()),
+                "forward" | "ffill" => Fill::Forward,
+                "backward" | "bfill" => Fill::Backward,
+                "mean" => Fill::Mean,
+                value => Fill::Value(unquote(value)),
+            })),
+            "round" => match rest {
+                "" => Ok(Transform::Round(0)),
+                digits => digits.parse().map(Transform::Round).map_err(|_| format!("not a number of digits: {}", digits)),
+            },
+            "sort" => match rest {
+                "" | "asc" => Ok(Transform::Sort(false)),
+                "desc" => Ok(Transform::Sort(true)),
+                order => Err(format!("sort asc or desc, not {}", order)),
+            },
+            _ => Err(format!("usage: {}", USAGE)),
+        }
+    }
+
+    /// Applies the transform to column `c` and describes what it did.
+    pub fn apply(&self, c: usize, headers: &mut Vec<String>, records: &mut [StringRecord]) -> String {
+        let name = headers[c].clone();
+        match self {
+            Transform::Cast(cast) => {
+                let mut failed = 0;
+                map_column(records, c, |v| {
+                    if v.trim().is_empty() {
+                        return String::new();
+                    }
+                    cast_value(v, *cast).unwrap_or_else(|| {
+                        failed += 1;
+                        String::new()
+                    })
+                });
+                match failed {
+                    0 => format!("cast {} to {}", name, cast),
+                    n => format!("cast {} to {}, {} value(s) could not be cast and are empty now", name, cast, n),
+                }
+            }
+            Transform::Trim => {
+                let changed = map_column(records, c, |v| v.trim().to_string());
+                format!("trimmed {} value(s) of {}", changed, name)
+            }
+            Transform::Case(case) => {
+                let changed = map_column(records, c, |v| match case {
+                    Case::Upper => v.to_uppercase(),
+                    Case::Lower => v.to_lowercase(),
+                    Case::Title => title_case(v),
+                });
+                format!("changed the case of {} value(s) of {}", changed, name)
+            }
+            Transform::Replace(regex, replacement) => {
+                let changed = map_column(records, c, |v| regex.replace_all(v, replacement.as_str()).to_string());
+                format!("replaced in {} value(s) of {}", changed, name)
+            }
+            Transform::Split(delimiter, width) => {
+                let parts = width.unwrap_or_else(|| split_width(records, c, delimiter));
+                let mut names: Vec<String> = vec![];
+                for i in 1..=parts {
+                    let taken = |n: &str| names.iter().any(|m| m == n) || headers.iter().enumerate().any(|(j, h)| j != c && h == n);
+                    names.push(free_name(&format!("{}/{}", name, i), taken));
+                }
+                headers.splice(c..=c, names);
+                for record in records.iter_mut() {
+                    let value = record.get(c).unwrap_or("").to_string();
+                    // the last column takes whatever does not fit
+                    let mut split: Vec<&str> = value.splitn(parts, delimiter.as_str()).collect();
+                    split.resize(parts, "");
+                    *record = splice(record, c..c + 1, split);
+                }
+                format!("split {} into {} columns", name, parts)
+            }
+            Transform::Merge(columns, separator) => {
+                let others: Vec<usize> = columns.iter().filter_map(|n| headers.iter().position(|h| h == n)).filter(|o| *o != c).collect();
+                for record in records.iter_mut() {
+                    let values: Vec<&str> = std::iter::once(c).chain(others.iter().copied()).map(|i| record.get(i).unwrap_or("")).collect();
+                    let merged = values.into_iter().filter(|v| !v.is_empty()).collect::<Vec<&str>>().join(separator);
+                    let fields: StringRecord = record
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| !others.contains(i))
+                        .map(|(i, v)| if i == c { merged.as_str() } else { v })
+                        .collect();
+                    *record = fields;
+                }
+                let mut i = 0;
+                headers.retain(|_| {
+                    i += 1;
+                    !others.contains(&(i - 1))
+                });
+                format!("merged {} into {}", columns.join(", "), name)
+            }
+            Transform::Fill(fill) => {
+                let value_at = |r: &StringRecord| r.get(c).unwrap_or("").to_string();
+                let filled = match fill {
+                    Fill::Value(value) => map_column(records, c, |v| if v.is_empty() { value.clone() } else { v.to_string() }),
+                    Fill::Mean => {
+                        let numbers: Vec<f64> = records.iter().filter_map(|r| r.get(c)?.trim().parse::<f64>().ok()).collect();
+                        if numbers.is_empty() {
+                            return format!("{} has no numbers to take the mean of", name);
+                        }
+                        let mean = expr::format_number(numbers.iter().sum::<f64>() / numbers.len() as f64);
+                        map_column(records, c, |v| if v.is_empty() { mean.clone() } else { v.to_string() })
+                    }
+                    Fill::Forward | Fill::Backward => {
+                        let order: Vec<usize> = match fill {
+                            Fill::Forward => (0..records.len()).collect(),
+                            _ => (0..records.len()).rev().collect(),
+                        };
+                        let mut last = String::new();
+                        let mut filled = 0;
+                        for i in order {
+                            let value = value_at(&records[i]);
+                            if value.is_empty() && !last.is_empty() {
+                                records[i] = splice(&records[i], c..c + 1, vec![last.as_str()]);
+                                filled += 1;
+                            } else if !value.is_empty() {
+                                last = value;
+                            }
+                        }
+                        filled
+                    }
+                };
+                format!("filled {} empty value(s) of {}", filled, name)
+            }
+            Transform::Round(digits) => {
+                let changed = map_column(records, c, |v| match v.trim().parse::<f64>() {
+                    Ok(n) => format!("{:.*}", *digits, n),
+                    Err(_) => v.to_string(),
+                });
+                format!("rounded {} value(s) of {}", changed, name)
+            }
+            Transform::Sort(descending) => {
+                records.sort_by(|a, b| {
+                    let (a, b) = (Value::from_cell(a.get(c).unwrap_or("")), Value::from_cell(b.get(c).unwrap_or("")));
+                    match a.compare(&b) {
+                        Some(ordering) if *descending => ordering.reverse(),
+                        Some(ordering) => ordering,
+                        None => (a == Value::Null).cmp(&(b == Value::Null)),
+                    }
+                });
+                format!("sorted by {}{}", name, if *descending { " descending" } else { "" })
+            }
+        }
+    }
+
+    /// Whether the column holds numbers afterwards, when the transform says
+    /// so. Rounding leaves text as it is, so it goes by the values.
+    pub fn numeric(&self) -> Option<bool> {
+        match self {
+            Transform::Cast(Cast::Integer | Cast::Float) => Some(true),
+            Transform::Cast(_) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Whether running the transform on some rows gives what running it on
+    /// the whole table gives for them. Sorts and fills from the mean or the
+    /// rows around a value need the whole table.
+    pub fn streams(&self) -> bool {
+        !matches!(self, Transform::Sort(_) | Transform::Fill(Fill::Forward | Fill::Backward | Fill::Mean))
+    }
+}
+
+/// A change to the table, kept so it can be undone, replayed on rows read
+/// later and saved as part of a pipeline.
+#[derive(Clone, Debug)]
+pub enum Step {
+    Derive(Derived),
+    Transform { column: String, command: String, transform: Transform },
+    // keeps the rows the expression holds for
+    Filter { expression: String, expr: Expr },
+}
+
+impl Step {
+    pub fn transform(column: &str, command: &str, headers: &[String]) -> Result<Step, String> {
+        Ok(Step::Transform {
+            column: column.to_string(),
+            command: command.trim().to_string(),
+            transform: Transform::parse(command, headers)?,
+        })
+    }
+
+    pub fn filter(expression: &str, headers: &[String]) -> Result<Step, String> {
+        let expression = expression.trim().to_string();
+        let expr = Expr::parse(&expression, headers)?;
+        Ok(Step::Filter { expression, expr })
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Step::Derive(derived) => format!("{} = {}", derived.name, derived.expression),
+            Step::Transform { column, command, .. } => format!("{}: {}", column, command),
+            Step::Filter { expression, .. } => format!("where {}", expression),
+        }
+    }
+
+    /// Fixes what the step would otherwise take from the rows it runs on, so
+    /// rows read later come out the same: the number of columns a split
+    /// makes.
+    pub fn pin(&mut self, headers: &[String], records: &[StringRecord]) {
+        if let Step::Transform { column, transform: Transform::Split(delimiter, width @ None), .. } = self {
+            if let Some(c) = headers.iter().position(|h| h == column) {
+                *width = Some(split_width(records, c, delimiter));
+            }
+        }
+    }
+
+    /// The same step over other headers, e.g. those of a reloaded file.
+    pub fn reparse(&self, headers: &[String]) -> Result<Step, String> {
+        match self {
+            Step::Derive(derived) => derived.reparse(headers).map(Step::Derive),
+            Step::Transform { column, command, .. } => Step::transform(column, command, headers),
+            Step::Filter { expression, .. } => Step::filter(expression, headers),
+        }
+    }
+
+    pub fn apply(&self, headers: &mut Vec<String>, records: &mut Vec<StringRecord>) -> Result<String, String> {
+        match self {
+            Step::Derive(derived) => {
+                let failed = derived.apply(records);
+                headers.push(derived.name.clone());
+                Ok(match failed {
+                    0 => format!("added column {}", derived.name),
+                    n => format!("added column {}, {} row(s) could not be computed", derived.name, n),
+                })
+            }
+            Step::Transform { column, transform, .. } => {
+                let c = headers.iter().position(|h| h == column).ok_or(format!("no column {}", column))?;
+                Ok(transform.apply(c, headers, records))
+            }
+            Step::Filter { expression, expr } => {
+                let before = records.len();
+                records.retain(|r| expr.eval(r).is_ok_and(|v| v.truthy()));
+                Ok(format!("kept {} of {} row(s) where {}", records.len(), before, expression))
+            }
+        }
+    }
+
+    /// The column the step writes to, as named afterwards.
+    pub fn column(&self) -> Option<&str> {
+        match self {
+            Step::Derive(derived) => Some(&derived.name),
+            Step::Transform { column, .. } => Some(column),
+            Step::Filter { .. } => None,
+        }
+    }
+
+    pub fn numeric(&self) -> Option<bool> {
+        match self {
+            Step::Derive(_) | Step::Filter { .. } => None,
+            Step::Transform { transform, .. } => transform.numeric(),
+        }
+    }
+
+    /// Whether the step can run on rows read later on their own.
+    pub fn streams(&self) -> bool {
+        match self {
+            Step::Derive(_) | Step::Filter { .. } => true,
+            Step::Transform { transform, .. } => transform.streams(),
+        }
+    }
+}
+
+/// The table as it was before a step, what undo goes back to.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub headers: Vec<String>,
+    pub items: Vec<StringRecord>,
+    pub numeric: Option<Vec<bool>>,
+}
+
+impl Snapshot {
+    /// Runs a step on the table, keeping the numeric flags up to date.
+    pub fn apply(&mut self, step: &Step) -> Result<String, String> {
+        let old = self.headers.clone();
+        let summary = step.apply(&mut self.headers, &mut self.items)?;
+        if let Some(flags) = self.numeric.as_mut() {
+            *flags = numeric_flags(step, &old, flags, &self.headers, &self.items);
+        }
+        Ok(summary)
+    }
+
+    /// The table after `steps`, skipping those that no longer apply.
+    pub fn replay(&self, steps: &[Step]) -> Snapshot {
+        let mut table = self.clone();
+        for step in steps {
+            let _ = table.apply(step);
+        }
+        table
+    }
+}
+
+/// The numeric flags of the columns after `step` turned `old` headers into
+/// `headers`: columns it wrote to are looked at again, the others keep theirs.
+pub fn numeric_flags(step: &Step, old: &[String], flags: &[bool], headers: &[String], records: &[StringRecord]) -> Vec<bool> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(c, h)| match old.iter().position(|o| o == h) {
+            _ if step.column() == Some(h.as_str()) => step.numeric().unwrap_or_else(|| derived::is_numeric(records, c)),
+            Some(o) => flags.get(o).copied().unwrap_or(false),
+            None => derived::is_numeric(records, c),
+        })
+        .collect()
+}
+
+/// `name`, or when it is taken the first free of `name_2`, `name_3`, ...
+fn free_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{}_{}", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// The most parts any value of column `c` splits into.
+fn split_width(records: &[StringRecord], c: usize, delimiter: &str) -> usize {
+    records.iter().map(|r| r.get(c).unwrap_or("").split(delimiter).count()).max().unwrap_or(1)
+}
+
+/// Rewrites column `c` and returns how many values changed.
+fn map_column(records: &mut [StringRecord], c: usize, mut f: impl FnMut(&str) -> String) -> usize {
+    let mut changed = 0;
+    for record in records.iter_mut() {
+        let Some(value) = record.get(c) else { continue };
+        let new = f(value);
+        if new != value {
+            *record = splice(record, c..c + 1, vec![new.as_str()]);
+            changed += 1;
+        }
+    }
+    changed
+}
+
+fn splice(record: &StringRecord, range: std::ops::Range<usize>, fields: Vec<&str>) -> StringRecord {
+    let mut all: Vec<&str> = record.iter().collect();
+    let end = range.end.min(all.len());
+    all.splice(range.start.min(end)..end, fields);
+    all.into_iter().collect()
+}
+
+fn cast_value(value: &str, cast: Cast) -> Option<String> {
+    let value = value.trim();
+    match cast {
+        Cast::Integer => value.parse::<f64>().ok().map(|n| (n.round() as i64).to_string()),
+        Cast::Float => value.parse::<f64>().ok().map(|n| format!("{:?}", n)),
+        Cast::Text => Some(value.to_string()),
+        Cast::Date => expr::parse_date(value).map(|d| d.format("%Y-%m-%d").to_string()),
+        Cast::Boolean => match value.to_lowercase().as_str() {
+            "true" | "yes" | "y" | "1" => Some("true".to_string()),
+            "false" | "no" | "n" | "0" => Some("false".to_string()),
+            _ => None,
+        },
+    }
+}
+
+fn title_case(value: &str) -> String {
+    let mut start = true;
+    value
+        .chars()
+        .map(|c| {
+            let mapped: String = match start {
+                true => c.to_uppercase().collect(),
+                false => c.to_lowercase().collect(),
+            };
+            start = !c.is_alphanumeric();
+            mapped
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    let quoted = value.len() >= 2 && (value.starts_with('"') && value.ends_with('"') || value.starts_with('\'') && value.ends_with('\''));
+    match quoted {
+        true => value[1..value.len() - 1].to_string(),
+        false => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(headers: &[&str], rows: &[&[&str]]) -> Snapshot {
+        Snapshot {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            items: rows.iter().map(|r| StringRecord::from(r.to_vec())).collect(),
+            numeric: Some(vec![false; headers.len()]),
+        }
+    }
+
+    fn rows(table: &Snapshot) -> Vec<Vec<&str>> {
+        table.items.iter().map(|r| r.iter().collect()).collect()
+    }
+
+    fn step(table: &Snapshot, column: &str, command: &str) -> Step {
+        Step::transform(column, command, &table.headers).unwrap()
+    }
+
+    #[test]
+    fn split_keeps_the_width_it_was_pinned_to() {
+        let mut later = table(&["id", "name"], &[&["3", "d e f"]]);
+        let mut table = table(&["id", "name"], &[&["1", "a b"], &["2", "c"]]);
+        let mut split = step(&table, "name", "split ' '");
+        split.pin(&table.headers, &table.items);
+        table.apply(&split).unwrap();
+        assert_eq!(table.headers, vec!["id", "name/1", "name/2"]);
+        assert_eq!(rows(&table), vec![vec!["1", "a", "b"], vec!["2", "c", ""]]);
+
+        // rows read later with more parts keep the rest in the last column
+        later.apply(&split).unwrap();
+        assert_eq!(later.headers, vec!["id", "name/1", "name/2"]);
+        assert_eq!(rows(&later), vec![vec!["3", "d", "e f"]]);
+    }
+
+    #[test]
+    fn split_columns_do_not_take_names_in_use() {
+        let mut free = table(&["name", "name/2", "name/1_2"], &[&["a b", "x", "y"]]);
+        let split = step(&free, "name", "split ' '");
+        free.apply(&split).unwrap();
+        assert_eq!(free.headers, vec!["name/1", "name/2_2", "name/2", "name/1_2"]);
+        assert_eq!(rows(&free), vec![vec!["a", "b", "x", "y"]]);
+
+        let mut taken = table(&["name", "name/1"], &[&["a b", "x"]]);
+        taken.apply(&split).unwrap();
+        assert_eq!(taken.headers, vec!["name/1_2", "name/2", "name/1"]);
+    }
+
+    #[test]
+    fn merge_drops_the_merged_columns_and_skips_empty_values() {
+        let mut table = table(&["first", "id", "last"], &[&["Ada", "1", "Lovelace"], &["", "2", "Hopper"]]);
+        let merge = step(&table, "first", "merge last with \" \"");
+        table.apply(&merge).unwrap();
+        assert_eq!(table.headers, vec!["first", "id"]);
+        assert_eq!(rows(&table), vec![vec!["Ada Lovelace", "1"], vec!["Hopper", "2"]]);
+    }
+
+    #[test]
+    fn fills_empty_cells() {
+        let base = table(&["n"], &[&[""], &["1"], &[""], &["3"], &[""]]);
+        let filled = |command: &str| {
+            let mut table = base.replay(&[]);
+            table.apply(&step(&base, "n", command)).unwrap();
+            table.items.iter().map(|r| r[0].to_string()).collect::<Vec<String>>()
+        };
+        assert_eq!(filled("fill 0"), vec!["0", "1", "0", "3", "0"]);
+        assert_eq!(filled("fill forward"), vec!["", "1", "1", "3", "3"]);
+        assert_eq!(filled("fill backward"), vec!["1", "1", "3", "3", ""]);
+        assert_eq!(filled("fill mean"), vec!["2", "1", "2", "3", "2"]);
+
+        let mut thirds = table(&["n"], &[&["1"], &["2"], &["2"], &[""]]);
+        thirds.apply(&step(&thirds, "n", "fill mean")).unwrap();
+        assert_eq!(thirds.items[3][0].to_string(), "1.6666666666666667");
+    }
+
+    #[test]
+    fn sorts_and_fills_from_other_rows_do_not_stream() {
+        let base = table(&["n"], &[&["1"]]);
+        assert!(!step(&base, "n", "fill forward").streams());
+        assert!(!step(&base, "n", "fill mean").streams());
+        assert!(!step(&base, "n", "sort desc").streams());
+        assert!(step(&base, "n", "fill 0").streams());
+        assert!(step(&base, "n", "split ,").streams());
+    }
+
+    #[test]
+    fn rows_read_later_are_sorted_into_the_table() {
+        let mut base = table(&["n"], &[&["3"], &["1"]]);
+        let sort = step(&base, "n", "sort");
+        assert_eq!(rows(&base.replay(std::slice::from_ref(&sort))), vec![vec!["1"], vec!["3"]]);
+        base.items.push(StringRecord::from(vec!["2"]));
+        assert_eq!(rows(&base.replay(&[sort])), vec![vec!["1"], vec!["2"], vec!["3"]]);
+    }
+
+    #[test]
+    fn filters_leave_out_empty_cells_unless_asked_for_null() {
+        let base = table(&["amount"], &[&["5"], &[""], &["20"], &[""]]);
+        let filtered = |expression: &str| {
+            let mut table = base.clone();
+            table.apply(&Step::filter(expression, &table.headers).unwrap()).unwrap();
+            table.items.iter().map(|r| r[0].to_string()).collect::<Vec<String>>()
+        };
+        assert_eq!(filtered("amount < 10"), vec!["5"]);
+        assert_eq!(filtered("amount >= 10"), vec!["20"]);
+        assert_eq!(filtered("amount == null"), vec!["", ""]);
+    }
+
+    #[test]
+    fn round_goes_by_the_values_for_the_numeric_flag() {
+        let mut numbers = table(&["n"], &[&["1.26"], &["2"]]);
+        numbers.apply(&step(&numbers, "n", "round 1")).unwrap();
+        assert_eq!(rows(&numbers), vec![vec!["1.3"], vec!["2.0"]]);
+        assert_eq!(numbers.numeric, Some(vec![true]));
+
+        let mut text = table(&["n"], &[&["a"], &["2.5"]]);
+        text.apply(&step(&text, "n", "round")).unwrap();
+        assert_eq!(rows(&text), vec![vec!["a"], vec!["2"]]);
+        assert_eq!(text.numeric, Some(vec![false]));
+    }
+
+    #[test]
+    fn cast_empties_what_does_not_convert() {
+        let mut table = table(&["n"], &[&["1.9"], &["x"], &[""]]);
+        table.apply(&step(&table, "n", "cast integer")).unwrap();
+        assert_eq!(rows(&table), vec![vec!["2"], vec![""], vec![""]]);
+        assert_eq!(table.numeric, Some(vec![true]));
+        table.apply(&step(&table, "n", "cast text")).unwrap();
+        assert_eq!(table.numeric, Some(vec![false]));
+    }
+
+    #[test]
+    fn undo_replays_the_remaining_steps_from_the_snapshot() {
+        let base = table(&["name", "n"], &[&[" a ", "2"], &["b", ""]]);
+        let steps = vec![step(&base, "name", "trim"), step(&base, "n", "fill 1"), step(&base, "name", "upper")];
+        let all = base.replay(&steps);
+        assert_eq!(rows(&all), vec![vec!["A", "2"], vec!["B", "1"]]);
+        let undone = base.replay(&steps[..2]);
+        assert_eq!(rows(&undone), vec![vec!["a", "2"], vec!["b", "1"]]);
+        assert_eq!(rows(&base.replay(&[])), vec![vec![" a ", "2"], vec!["b", ""]]);
+    }
+}
@@ -0,0 +1,89 @@
+use csv::{StringRecord, Writer};
+use crate::tui::duplicates;
+use crate::tui::outliers::Outliers;
+
+
+/// What decides which rows the Data Explorer shows.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    // duplicate rows on these key columns (whole rows without any), grouped
+    Duplicates(Vec<usize>),
+    // rows with an outlier in any numeric column
+    Outliers,
+}
+
+/// A subset of `App::items` shown in the Data Explorer instead of all rows.
+pub struct RowView {
+    pub filter: Filter,
+    pub title: String,
+    // positions in `App::items`, in display order
+    pub rows: Vec<usize>,
+    // shown in an extra first column, e.g. the duplicate group and its size
+    pub label: String,
+    pub labels: Vec<String>,
+    pub generation: Option<usize>,
+}
+
+impl RowView {
+    pub fn new(filter: Filter) -> RowView {
+        RowView { filter, title: String::new(), rows: vec![], label: String::new(), labels: vec![], generation: None }
+    }
+
+    pub fn compute(&mut self, headers: &[String], records: &[StringRecord], outliers: &Outliers) {
+        match &self.filter {
+            Filter::Duplicates(keys) => {
+                let groups = duplicates::find(records, keys);
+                self.rows.clear();
+                self.labels.clear();
+                for (g, group) in groups.iter().enumerate() {
+                    self.rows.extend(&group.rows);
+                    self.labels.extend(group.rows.iter().map(|_| format!("#{} ×{}", g + 1, group.rows.len())));
+                }
+                let on = match keys.is_empty() {
+                    true => "whole rows".to_string(),
+                    false => keys.iter().map(|k| headers[*k].as_str()).collect::<Vec<_>>().join(", "),
+                };
+                self.label = "group".to_string();
+                self.title = format!(
+                    "Duplicates on {}: {} group(s), {} row(s) | (D) show all | (e) export deduplicated",
+                    on,
+                    groups.len(),
+                    self.rows.len()
+                );
+            }
+            Filter::Outliers => {
+                self.rows.clear();
+                self.labels.clear();
+                for (i, record) in records.iter().enumerate() {
+                    let columns: Vec<&str> = record
+                        .iter()
+                        .enumerate()
+                        .filter(|(c, field)| outliers.is_outlier_cell(*c, field))
+                        .map(|(c, _)| headers[c].as_str())
+                        .collect();
+                    if !columns.is_empty() {
+                        self.rows.push(i);
+                        self.labels.push(columns.join(", "));
+                    }
+                }
+                self.label = "outlier in".to_string();
+                self.title = format!(
+                    "Outliers ({}): {} of {} row(s) | (O) show all | (z) IQR/z-score",
+                    outliers.method.name(),
+                    self.rows.len(),
+                    records.len()
+                );
+            }
+        }
+    }
+}
+
+pub fn export(path: &str, headers: &[String], records: &[&StringRecord]) -> Result<(), csv::Error> {
+    let mut wtr = Writer::from_path(path)?;
+    wtr.write_record(headers)?;
+    for record in records {
+        wtr.write_record(*record)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}